@@ -52,13 +52,13 @@ use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
 use sp_api::impl_runtime_apis;
 use sp_runtime::{
 	Permill, Perbill, Perquintill, Percent, ApplyExtrinsicResult, impl_opaque_keys, generic,
-	create_runtime_str, FixedPointNumber,
+	create_runtime_str, FixedPointNumber, FixedU128,
 };
 use sp_runtime::curve::PiecewiseLinear;
 use sp_runtime::transaction_validity::{TransactionValidity, TransactionSource, TransactionPriority};
 use sp_runtime::traits::{
-	self, BlakeTwo256, Block as BlockT, StaticLookup, SaturatedConversion, ConvertInto, OpaqueKeys,
-	NumberFor,
+	self, BlakeTwo256, Block as BlockT, StaticLookup, SaturatedConversion, OpaqueKeys,
+	NumberFor, AccountIdConversion,
 };
 use sp_version::RuntimeVersion;
 #[cfg(any(feature = "std", test))]
@@ -212,7 +212,7 @@ impl frame_system::Config for Runtime {
 	type PalletInfo = PalletInfo;
 	type AccountData = pallet_balances::AccountData<Balance>;
 	type OnNewAccount = ();
-	type OnKilledAccount = ();
+	type OnKilledAccount = Vesting;
 	type SystemWeightInfo = frame_system::weights::SubstrateWeight<Runtime>;
 	type SS58Prefix = SS58Prefix;
 	type OnSetCode = ();
@@ -1044,13 +1044,93 @@ impl pallet_society::Config for Runtime {
 
 parameter_types! {
 	pub const MinVestedTransfer: Balance = 100 * DOLLARS;
+	pub const MaxIncomingVestingSchedules: u32 = 4;
+	pub const IncomingVestingSchedulePeriod: BlockNumber = DAYS;
+	pub const VestingScheduleDeposit: Balance = 1 * DOLLARS;
+	// Must be created with `force_create` before vesting positions can be minted.
+	pub const VestingPositionClass: u32 = u32::max_value();
+	pub const VestingUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 4;
+	pub const VestingMaxUnsignedValidity: BlockNumber = HOURS;
+	pub const VestingContingencyPeriod: BlockNumber = 365 * DAYS;
+	pub const VestingAutoVestLimit: u32 = 16;
+	pub const VestingMaxAutoVestsPerBlock: u32 = 16;
+	pub const VestingEarlyExitPenalty: Perbill = Perbill::from_percent(10);
+	pub const VestingMaxClaimProofLength: u32 = 32;
+	pub const VestingMaxMetadataLen: u32 = 32;
+	pub const VestingMaxArchived: u32 = 16;
+	pub VestingMaxUnlockMultiplier: FixedU128 = FixedU128::saturating_from_integer(10);
+	pub const VestingReserveId: [u8; 8] = *b"vesting ";
+}
+
+/// The id of a parachain, whose sovereign account is derived as on the relay chain.
+#[derive(Encode, Decode)]
+pub struct ParaId(u32);
+
+impl sp_runtime::TypeId for ParaId {
+	const TYPE_ID: [u8; 4] = *b"para";
+}
+
+/// Derives the targets of vested transfers to utility derivatives and parachains.
+pub struct VestingBeneficiaries;
+impl pallet_vesting::DeriveBeneficiary<AccountId> for VestingBeneficiaries {
+	type Location = u32;
+
+	fn derivative_account(owner: &AccountId, index: u16) -> Option<AccountId> {
+		Some(Utility::derivative_account_id(owner.clone(), index))
+	}
+
+	fn sovereign_account(para_id: &u32) -> Option<AccountId> {
+		Some(ParaId(*para_id).into_account())
+	}
 }
 
 impl pallet_vesting::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
-	type BlockNumberToBalance = ConvertInto;
+	type ReserveId = VestingReserveId;
 	type MinVestedTransfer = MinVestedTransfer;
+	type MaxTotalVested = ();
+	type MinVestedTransferPolicy = ();
+	type MaxIncomingSchedules = MaxIncomingVestingSchedules;
+	type IncomingSchedulePeriod = IncomingVestingSchedulePeriod;
+	type ScheduleDeposit = VestingScheduleDeposit;
+	type ArchiveCompletedSchedules = ();
+	type MaxArchived = VestingMaxArchived;
+	type ScheduledCall = Call;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as traits::Verify>::Signer;
+	type UnsignedPriority = VestingUnsignedPriority;
+	type MaxUnsignedValidity = VestingMaxUnsignedValidity;
+	type AutoVestLimit = VestingAutoVestLimit;
+	type MaxAutoVestsPerBlock = VestingMaxAutoVestsPerBlock;
+	type BalanceNeeded = ();
+	type VestPrecondition = frame_support::traits::All<AccountId>;
+	type UnlockOracle = ();
+	type BonusRate = ();
+	type BonusPayout = ();
+	type Positions = Uniques;
+	type PositionClass = VestingPositionClass;
+	type ContingencyPeriod = VestingContingencyPeriod;
+	type SweepDelay = ();
+	type SweepDestination = ();
+	type EarlyExitPenalty = VestingEarlyExitPenalty;
+	type EarlyExitPenaltyHandler = Treasury;
+	type ClaimsPayout = pallet_vesting::PayByMinting<Runtime>;
+	type Beneficiaries = VestingBeneficiaries;
+	type MaxClaimProofLength = VestingMaxClaimProofLength;
+	type MaxMetadataLen = VestingMaxMetadataLen;
+	type PermissionedVestOther = ();
+	type NoOpVestWhenNotVesting = ();
+	type VestOperators = ();
+	type VestedTransferOrigin = frame_system::EnsureSigned<AccountId>;
+	type ForceOrigin = EnsureRootOrHalfCouncil;
+	type UnlockMultiplierOrigin = EnsureRootOrHalfCouncil;
+	type MaxUnlockMultiplier = VestingMaxUnlockMultiplier;
+	type OnVestingCompleted = ();
+	type OnVestUnlocked = ();
+	type OnLockedChanged = ();
 	type WeightInfo = pallet_vesting::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1201,7 +1281,7 @@ construct_runtime!(
 		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>},
 		Society: pallet_society::{Pallet, Call, Storage, Event<T>, Config<T>},
 		Recovery: pallet_recovery::{Pallet, Call, Storage, Event<T>},
-		Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>},
+		Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
 		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
 		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>},
 		Multisig: pallet_multisig::{Pallet, Call, Storage, Event<T>},
@@ -1475,6 +1555,57 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_vesting_rpc_runtime_api::VestingApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn unlockable_at(who: AccountId, at: BlockNumber) -> Balance {
+			Vesting::unlockable_at(&who, at)
+		}
+		fn next_unlock_block(who: AccountId) -> Option<(BlockNumber, Balance)> {
+			Vesting::next_unlock_block(&who)
+		}
+		fn vesting_timeline(who: AccountId, until: BlockNumber) -> Vec<(BlockNumber, Balance)> {
+			Vesting::vesting_timeline(&who, until)
+		}
+		fn total_unvested(
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> (Balance, Option<AccountId>) {
+			Vesting::total_unvested(start_after, limit)
+		}
+		fn vesting_summaries(
+			accounts: Vec<AccountId>,
+		) -> Vec<Option<pallet_vesting::VestingSummary<Balance, BlockNumber>>> {
+			Vesting::vesting_summaries(&accounts)
+		}
+		fn vesting_accounts(
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<(AccountId, pallet_vesting::VestingSummary<Balance, BlockNumber>)> {
+			Vesting::vesting_accounts(start_after, limit)
+		}
+		fn schedule_status(
+			who: AccountId,
+		) -> Option<pallet_vesting::ScheduleStatus<Balance, BlockNumber>> {
+			Vesting::schedule_status(&who)
+		}
+		fn aggregate_schedule(who: AccountId) -> Option<(Balance, BlockNumber)> {
+			Vesting::aggregate_schedule(&who)
+		}
+		fn funded_schedules(
+			source: AccountId,
+		) -> Vec<(AccountId, pallet_vesting::VestingInfo<Balance, BlockNumber>)> {
+			Vesting::funded_schedules(&source)
+		}
+		fn remaining_schedule_slots(who: AccountId) -> u32 {
+			Vesting::remaining_schedule_slots(&who)
+		}
+		fn validate_vested_transfer(
+			target: AccountId,
+			schedule: pallet_vesting::VestingInfo<Balance, BlockNumber>,
+		) -> Result<(), sp_runtime::DispatchError> {
+			Vesting::validate_vested_transfer(&target, schedule)
+		}
+	}
+
 	impl pallet_mmr::primitives::MmrApi<
 		Block,
 		mmr::Hash,
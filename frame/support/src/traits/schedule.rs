@@ -93,6 +93,36 @@ pub trait Anon<BlockNumber, Call, Origin> {
 	fn next_dispatch_time(address: Self::Address) -> Result<BlockNumber, ()>;
 }
 
+/// Schedules nothing, for runtimes without a scheduler.
+impl<BlockNumber, Call, Origin> Anon<BlockNumber, Call, Origin> for () {
+	type Address = ();
+
+	fn schedule(
+		_: DispatchTime<BlockNumber>,
+		_: Option<Period<BlockNumber>>,
+		_: Priority,
+		_: Origin,
+		_: Call,
+	) -> Result<Self::Address, DispatchError> {
+		Err(DispatchError::Other("No scheduler"))
+	}
+
+	fn cancel(_: Self::Address) -> Result<(), ()> {
+		Err(())
+	}
+
+	fn reschedule(
+		_: Self::Address,
+		_: DispatchTime<BlockNumber>,
+	) -> Result<Self::Address, DispatchError> {
+		Err(DispatchError::Other("No scheduler"))
+	}
+
+	fn next_dispatch_time(_: Self::Address) -> Result<BlockNumber, ()> {
+		Err(())
+	}
+}
+
 /// A type that can be used as a scheduler.
 pub trait Named<BlockNumber, Call, Origin> {
 	/// An address which can be used for removing a scheduled task.
@@ -27,7 +27,7 @@ use codec::MaxEncodedLen;
 mod reservable;
 pub use reservable::{ReservableCurrency, NamedReservableCurrency};
 mod lockable;
-pub use lockable::{LockableCurrency, VestingSchedule, LockIdentifier};
+pub use lockable::{LockableCurrency, VestingSchedule, VestedTransfer, UnvestedBalance, LockIdentifier};
 
 /// Abstraction over a fungible assets system.
 pub trait Currency<AccountId> {
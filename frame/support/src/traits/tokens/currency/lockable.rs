@@ -21,6 +21,7 @@ use crate::dispatch::DispatchResult;
 use crate::traits::misc::Get;
 use super::Currency;
 use super::super::misc::WithdrawReasons;
+use sp_runtime::traits::Zero;
 
 /// An identifier for a lock. Used for disambiguating different locks so that
 /// they can be individually replaced or removed.
@@ -97,8 +98,52 @@ pub trait VestingSchedule<AccountId> {
 		starting_block: Self::Moment,
 	) -> DispatchResult;
 
+	/// Checks if `add_vesting_schedule` would work against `who`.
+	fn can_add_vesting_schedule(
+		who: &AccountId,
+		locked: <Self::Currency as Currency<AccountId>>::Balance,
+		per_block: <Self::Currency as Currency<AccountId>>::Balance,
+		starting_block: Self::Moment,
+	) -> DispatchResult;
+
 	/// Remove a vesting schedule for a given account.
 	///
 	/// NOTE: This doesn't alter the free balance of the account.
 	fn remove_vesting_schedule(who: &AccountId);
 }
+
+/// A vested transfer over a currency. This allows a transferred amount to vest over time.
+pub trait VestedTransfer<AccountId> {
+	/// The quantity used to denote time; usually just a `BlockNumber`.
+	type Moment;
+
+	/// The currency that this schedule applies to.
+	type Currency: Currency<AccountId>;
+
+	/// Transfer `locked` from `source` to `target`, placing it under a vesting schedule which
+	/// unlocks `per_block` every block after `starting_block`.
+	///
+	/// If the schedule cannot be created, an `Err` is returned and nothing is transferred.
+	fn vested_transfer(
+		source: &AccountId,
+		target: &AccountId,
+		locked: <Self::Currency as Currency<AccountId>>::Balance,
+		per_block: <Self::Currency as Currency<AccountId>>::Balance,
+		starting_block: Self::Moment,
+	) -> DispatchResult;
+}
+
+/// Reports the balance of an account which is locked because it has not yet vested.
+///
+/// Governance configurations can use this to decide whether such funds count toward voting power,
+/// rather than relying on the vesting lock happening to overlap with voting locks.
+pub trait UnvestedBalance<AccountId, Balance> {
+	/// The balance of `who` which is locked until it vests.
+	fn unvested_balance(who: &AccountId) -> Balance;
+}
+
+impl<AccountId, Balance: Zero> UnvestedBalance<AccountId, Balance> for () {
+	fn unvested_balance(_: &AccountId) -> Balance {
+		Zero::zero()
+	}
+}
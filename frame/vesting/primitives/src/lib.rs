@@ -0,0 +1,419 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Primitives of the vesting pallet: the vesting schedule of an account and the math deriving
+//! how much of it is locked over time. They don't depend on FRAME, so that off-chain tooling can
+//! share them.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, One, UniqueSaturatedFrom, UniqueSaturatedInto, Zero},
+	FixedPointNumber, FixedU128, RuntimeDebug,
+};
+
+/// Convert a number of blocks into a balance, saturating if it doesn't fit.
+pub fn block_number_to_balance<BlockNumber, Balance>(n: BlockNumber) -> Balance where
+	BlockNumber: UniqueSaturatedInto<u128>,
+	Balance: UniqueSaturatedFrom<u128>,
+{
+	let n: u128 = n.unique_saturated_into();
+	Balance::unique_saturated_from(n)
+}
+
+/// Struct to encode the vesting schedule of an individual account.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase", deny_unknown_fields))]
+pub struct VestingInfo<Balance, BlockNumber> {
+	/// Locked amount at genesis.
+	pub locked: Balance,
+	/// Amount that gets unlocked every block after `starting_block`.
+	pub per_block: Balance,
+	/// Starting block for unlocking(vesting).
+	pub starting_block: BlockNumber,
+}
+
+impl<Balance, BlockNumber> VestingInfo<Balance, BlockNumber> {
+	/// Create a new schedule, without checking whether it is valid.
+	pub fn new(locked: Balance, per_block: Balance, starting_block: BlockNumber) -> Self {
+		Self { locked, per_block, starting_block }
+	}
+}
+
+impl<Balance: Zero, BlockNumber: Zero> VestingInfo<Balance, BlockNumber> {
+	/// Start constructing a new schedule, checked for validity once built.
+	///
+	/// By default nothing is locked and vesting starts at block zero.
+	pub fn builder() -> VestingInfoBuilder<Balance, BlockNumber> {
+		VestingInfoBuilder {
+			schedule: Self::new(Zero::zero(), Zero::zero(), Zero::zero()),
+		}
+	}
+}
+
+/// A builder for `VestingInfo` which checks that the schedule is valid.
+#[derive(Clone, RuntimeDebug)]
+pub struct VestingInfoBuilder<Balance, BlockNumber> {
+	schedule: VestingInfo<Balance, BlockNumber>,
+}
+
+impl<
+	Balance: AtLeast32BitUnsigned + Copy,
+	BlockNumber: AtLeast32BitUnsigned + Copy,
+> VestingInfoBuilder<Balance, BlockNumber> {
+	/// Set the amount locked.
+	pub fn locked(mut self, locked: Balance) -> Self {
+		self.schedule.locked = locked;
+		self
+	}
+
+	/// Set the amount unlocked every block after `starting_block`.
+	pub fn per_block(mut self, per_block: Balance) -> Self {
+		self.schedule.per_block = per_block;
+		self
+	}
+
+	/// Set the block after which funds start unlocking.
+	pub fn starting_block(mut self, starting_block: BlockNumber) -> Self {
+		self.schedule.starting_block = starting_block;
+		self
+	}
+
+	/// Construct the schedule, failing if it doesn't lock funds or vest some every block.
+	pub fn build(self) -> Result<VestingInfo<Balance, BlockNumber>, &'static str> {
+		if self.schedule.is_valid() {
+			Ok(self.schedule)
+		} else {
+			Err("Vesting schedules must lock funds and vest some every block")
+		}
+	}
+}
+
+/// A vesting schedule as seen at a given block, for readable logs and test failures.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingInfoAt<Balance, BlockNumber> {
+	/// The schedule.
+	pub schedule: VestingInfo<Balance, BlockNumber>,
+	/// The block at which the schedule is seen.
+	pub now: BlockNumber,
+	/// Amount still locked at `now`.
+	pub remaining: Balance,
+	/// The block at which the schedule is fully vested.
+	pub ending_block: BlockNumber,
+}
+
+impl<Balance: sp_std::fmt::Display, BlockNumber: sp_std::fmt::Display> sp_std::fmt::Display
+	for VestingInfoAt<Balance, BlockNumber>
+{
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(
+			f,
+			"{} of {} locked at block {}, unlocking {} per block from block {} until block {}",
+			self.remaining,
+			self.schedule.locked,
+			self.now,
+			self.schedule.per_block,
+			self.schedule.starting_block,
+			self.ending_block,
+		)
+	}
+}
+
+impl<
+	Balance: AtLeast32BitUnsigned + Copy,
+	BlockNumber: AtLeast32BitUnsigned + Copy,
+> VestingInfo<Balance, BlockNumber> {
+	/// Whether the schedule locks some funds and unlocks some of them every block.
+	pub fn is_valid(&self) -> bool {
+		!self.locked.is_zero() && !self.per_block.is_zero()
+	}
+
+	/// The schedule with a zero `per_block` raised to one, so that it always completes.
+	pub fn correct(mut self) -> Self {
+		self.per_block = self.per_block.max(One::one());
+		self
+	}
+
+	/// The block at which the schedule is fully vested, when unlocking at its nominal rate.
+	pub fn ending_block(&self) -> BlockNumber {
+		let per_block = self.per_block.max(One::one());
+		let blocks: u128 =
+			(self.locked.saturating_add(per_block - One::one()) / per_block).unique_saturated_into();
+		self.starting_block.saturating_add(BlockNumber::unique_saturated_from(blocks))
+	}
+
+	/// The schedule as seen at block `now`, which displays the amount still locked and the
+	/// ending block alongside its fields.
+	pub fn at(&self, now: BlockNumber) -> VestingInfoAt<Balance, BlockNumber> {
+		VestingInfoAt {
+			schedule: *self,
+			now,
+			remaining: self.locked_at(now),
+			ending_block: self.ending_block(),
+		}
+	}
+
+	/// Amount locked at block `n`.
+	pub fn locked_at(&self, n: BlockNumber) -> Balance {
+		self.locked_at_scaled(n, FixedU128::one())
+	}
+
+	/// Amount locked at block `n`, with funds being unlocked `multiplier` times as fast.
+	pub fn locked_at_scaled(&self, n: BlockNumber, multiplier: FixedU128) -> Balance {
+		// Number of blocks that count toward vesting
+		// Saturating to 0 when n < starting_block
+		let vested_block_count: u128 = n.saturating_sub(self.starting_block).unique_saturated_into();
+		let vested_block_count =
+			Balance::unique_saturated_from(multiplier.saturating_mul_int(vested_block_count));
+		// Return amount that is still locked in vesting
+		let maybe_balance = vested_block_count.checked_mul(&self.per_block);
+		if let Some(balance) = maybe_balance {
+			self.locked.saturating_sub(balance)
+		} else {
+			Zero::zero()
+		}
+	}
+
+	/// Amount unlocked by block `n`, i.e. the part of `locked` which is no longer locked at `n`.
+	pub fn vested_at(&self, n: BlockNumber) -> Balance {
+		self.vested_at_scaled(n, FixedU128::one())
+	}
+
+	/// Amount unlocked by block `n`, with funds being unlocked `multiplier` times as fast.
+	pub fn vested_at_scaled(&self, n: BlockNumber, multiplier: FixedU128) -> Balance {
+		self.locked.saturating_sub(self.locked_at_scaled(n, multiplier))
+	}
+}
+
+/// A vesting schedule as kept in storage, tagged with the version of its layout so that new
+/// layouts can be introduced alongside the old ones rather than by migrating every schedule.
+///
+/// New versions must be added as new variants, leaving the existing ones untouched.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum VersionedVestingInfo<Balance, BlockNumber> {
+	/// A linear schedule.
+	V1(VestingInfo<Balance, BlockNumber>),
+	/// A linear schedule, along with the block at which it was created.
+	V2 { schedule: VestingInfo<Balance, BlockNumber>, created_at: BlockNumber },
+}
+
+impl<Balance, BlockNumber> VersionedVestingInfo<Balance, BlockNumber> {
+	/// Store `schedule`, created at block `created_at`, in the latest layout.
+	pub fn new(schedule: VestingInfo<Balance, BlockNumber>, created_at: BlockNumber) -> Self {
+		VersionedVestingInfo::V2 { schedule, created_at }
+	}
+
+	/// The schedule in the latest layout.
+	pub fn into_latest(self) -> VestingInfo<Balance, BlockNumber> {
+		match self {
+			VersionedVestingInfo::V1(schedule) => schedule,
+			VersionedVestingInfo::V2 { schedule, .. } => schedule,
+		}
+	}
+
+	/// The block at which the schedule was created, unless it was stored before this was
+	/// recorded.
+	pub fn created_at(self) -> Option<BlockNumber> {
+		match self {
+			VersionedVestingInfo::V1(_) => None,
+			VersionedVestingInfo::V2 { created_at, .. } => Some(created_at),
+		}
+	}
+
+	/// Replace the schedule, keeping the block at which it was created.
+	pub fn with_schedule(self, schedule: VestingInfo<Balance, BlockNumber>) -> Self {
+		match self {
+			VersionedVestingInfo::V1(_) => VersionedVestingInfo::V1(schedule),
+			VersionedVestingInfo::V2 { created_at, .. } =>
+				VersionedVestingInfo::V2 { schedule, created_at },
+		}
+	}
+}
+
+impl<Balance, BlockNumber> From<VestingInfo<Balance, BlockNumber>>
+	for VersionedVestingInfo<Balance, BlockNumber>
+{
+	fn from(schedule: VestingInfo<Balance, BlockNumber>) -> Self {
+		VersionedVestingInfo::V1(schedule)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn vesting_info_displays_progress() {
+		let schedule = VestingInfo { locked: 205u64, per_block: 10, starting_block: 10u64 };
+		assert_eq!(schedule.ending_block(), 31);
+		assert_eq!(
+			schedule.at(15).to_string(),
+			"155 of 205 locked at block 15, unlocking 10 per block from block 10 until block 31",
+		);
+		assert_eq!(schedule.at(40).remaining, 0);
+	}
+
+	#[test]
+	fn vesting_info_builder_validates() {
+		let schedule = VestingInfo { locked: 512u64, per_block: 64, starting_block: 10u64 };
+		assert_eq!(VestingInfo::new(512, 64, 10), schedule);
+		assert_eq!(
+			VestingInfo::<u64, u64>::builder().locked(512).per_block(64).starting_block(10).build(),
+			Ok(schedule),
+		);
+		assert!(VestingInfo::<u64, u64>::builder().locked(512).build().is_err());
+		assert!(VestingInfo::<u64, u64>::builder().per_block(64).build().is_err());
+	}
+
+	#[test]
+	fn vesting_info_serde_works() {
+		let schedule = VestingInfo { locked: 512u64, per_block: 64u64, starting_block: 10u64 };
+		let json = r#"{"locked":512,"perBlock":64,"startingBlock":10}"#;
+		assert_eq!(serde_json::to_string(&schedule).unwrap(), json);
+		assert_eq!(serde_json::from_str::<VestingInfo<u64, u64>>(json).unwrap(), schedule);
+		assert!(serde_json::from_str::<VestingInfo<u64, u64>>(
+			r#"{"locked":512,"per_block":64,"startingBlock":10}"#
+		).is_err());
+	}
+
+	#[test]
+	fn vested_at_works() {
+		let schedule = VestingInfo { locked: 205u64, per_block: 10, starting_block: 10u64 };
+		assert_eq!(schedule.vested_at(5), 0);
+		assert_eq!(schedule.vested_at(15), 50);
+		assert_eq!(schedule.vested_at(31), 205);
+		assert_eq!(schedule.vested_at(u64::max_value()), 205);
+		assert_eq!(schedule.vested_at_scaled(15, FixedU128::saturating_from_integer(2)), 100);
+	}
+
+	quickcheck::quickcheck! {
+		fn locked_at_never_exceeds_locked(locked: u64, per_block: u64, start: u64, n: u64) -> bool {
+			let schedule = VestingInfo { locked, per_block, starting_block: start };
+			schedule.locked_at(n) <= locked
+		}
+
+		fn locked_at_never_increases(
+			locked: u64,
+			per_block: u64,
+			start: u64,
+			n: u64,
+			m: u64
+		) -> bool {
+			let schedule = VestingInfo { locked, per_block, starting_block: start };
+			let (earlier, later) = (n.min(m), n.max(m));
+			schedule.locked_at(later) <= schedule.locked_at(earlier)
+		}
+
+		fn locked_at_is_zero_at_end(
+			locked: u64,
+			per_block: u64,
+			start: u64
+		) -> quickcheck::TestResult {
+			let schedule = VestingInfo { locked, per_block, starting_block: start };
+			if !schedule.is_valid() {
+				return quickcheck::TestResult::discard()
+			}
+			let duration = (locked as u128 + per_block as u128 - 1) / per_block as u128;
+			let end = start as u128 + duration;
+			if end > u64::max_value() as u128 {
+				return quickcheck::TestResult::discard()
+			}
+			let end = end as u64;
+			quickcheck::TestResult::from_bool(
+				schedule.locked_at(end).is_zero() &&
+					!schedule.locked_at(end - 1).is_zero()
+			)
+		}
+
+		fn vested_at_complements_locked_at(locked: u64, per_block: u64, start: u64, n: u64) -> bool {
+			let schedule = VestingInfo { locked, per_block, starting_block: start };
+			schedule.vested_at(n) + schedule.locked_at(n) == locked
+		}
+
+		fn locked_at_scaled_never_exceeds_locked_at(
+			locked: u64,
+			per_block: u64,
+			start: u64,
+			n: u64,
+			multiplier: u16
+		) -> bool {
+			let schedule = VestingInfo { locked, per_block, starting_block: start };
+			let multiplier = FixedU128::saturating_from_rational(100 + multiplier as u128, 100);
+			schedule.locked_at_scaled(n, multiplier) <= schedule.locked_at(n)
+		}
+	}
+
+	#[test]
+	fn correct_raises_zero_per_block() {
+		let schedule = VestingInfo { locked: 100u64, per_block: 0, starting_block: 10u64 };
+		assert!(!schedule.is_valid());
+		assert_eq!(schedule.correct(), VestingInfo { per_block: 1, ..schedule });
+		assert_eq!(schedule.correct().ending_block(), 110);
+		// Valid schedules are left as they are.
+		let schedule = VestingInfo { per_block: 10, ..schedule };
+		assert_eq!(schedule.correct(), schedule);
+	}
+
+	#[test]
+	fn ending_block_rounds_up() {
+		let schedule = VestingInfo { locked: 200u64, per_block: 10, starting_block: 10u64 };
+		assert_eq!(schedule.ending_block(), 30);
+		assert_eq!(VestingInfo { locked: 201, ..schedule }.ending_block(), 31);
+		let late = VestingInfo { starting_block: u64::max_value(), ..schedule };
+		assert_eq!(late.ending_block(), u64::max_value());
+	}
+
+	#[test]
+	fn locked_at_works() {
+		let schedule = VestingInfo { locked: 205u64, per_block: 10, starting_block: 10u64 };
+		assert_eq!(schedule.locked_at(5), 205);
+		assert_eq!(schedule.locked_at(10), 205);
+		assert_eq!(schedule.locked_at(15), 155);
+		assert_eq!(schedule.locked_at(30), 5);
+		assert_eq!(schedule.locked_at(31), 0);
+		assert_eq!(schedule.locked_at_scaled(15, FixedU128::saturating_from_rational(1, 2)), 185);
+	}
+
+	#[test]
+	fn versioned_vesting_info_decodes() {
+		let schedule = VestingInfo { locked: 512u64, per_block: 64, starting_block: 10u64 };
+
+		let v1 = VersionedVestingInfo::V1(schedule);
+		let encoded = v1.encode();
+		assert_eq!(encoded[0], 0);
+		assert_eq!(&encoded[1..], &schedule.encode()[..]);
+		assert_eq!(VersionedVestingInfo::decode(&mut &encoded[..]), Ok(v1));
+		assert_eq!(v1.into_latest(), schedule);
+		assert_eq!(v1.created_at(), None);
+
+		let v2 = VersionedVestingInfo::new(schedule, 5);
+		let encoded = v2.encode();
+		assert_eq!(encoded[0], 1);
+		assert_eq!(VersionedVestingInfo::decode(&mut &encoded[..]), Ok(v2));
+		assert_eq!(v2.into_latest(), schedule);
+		assert_eq!(v2.created_at(), Some(5));
+
+		let corrected = VestingInfo { per_block: 128, ..schedule };
+		assert_eq!(v2.with_schedule(corrected), VersionedVestingInfo::new(corrected, 5));
+		assert_eq!(v1.with_schedule(corrected), VersionedVestingInfo::V1(corrected));
+
+		// Unknown versions don't decode.
+		assert!(VersionedVestingInfo::<u64, u64>::decode(&mut &[2u8][..]).is_err());
+	}
+}
@@ -0,0 +1,196 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests of the vesting pallet against the state of a live chain.
+//!
+//! The tests are generic over the runtime, so that any runtime including the pallet can run them
+//! against its own chain, e.g. from a `#[tokio::test]` behind a feature flag:
+//!
+//! ```ignore
+//! pallet_vesting_remote_tests::test_vesting_state::<Runtime, Block>(Mode::Online(OnlineConfig {
+//! 	transport: "ws://localhost:9944".to_owned().into(),
+//! 	modules: vec!["Vesting".to_owned(), "Balances".to_owned(), "System".to_owned()],
+//! 	..Default::default()
+//! }))
+//! .await;
+//! ```
+//!
+//! The vesting locks are assumed to be held by `pallet-balances`.
+//!
+//! The tests of this crate run them against a chain of the node runtime, given by the
+//! `REMOTE_TESTS` environment variable:
+//!
+//! ```sh
+//! REMOTE_TESTS=ws://localhost:9944 cargo test -p pallet-vesting-remote-tests -- --ignored
+//! ```
+
+use frame_support::traits::{OnRuntimeUpgrade, VestingSchedule};
+use pallet_vesting::{LifetimeTotals, Vesting, VESTING_ID};
+use remote_externalities::{Builder, Mode};
+use sp_runtime::traits::{Block as BlockT, Saturating, Zero};
+
+const LOG_TARGET: &str = "remote-ext-tests::vesting";
+
+/// Load the state given by `mode` and run the pending migrations of the pallet, then check the
+/// vesting locks before and after replaying `vest` for every vesting account.
+///
+/// The locks aren't checked before migrating, as older storage versions lack the lifetime totals
+/// they are checked against. They must match exactly once migrated, including on accounts which
+/// have vested funds without claiming them, whose locks the migrations bring down to the totals.
+///
+/// `mode` must scrape at least the `Vesting`, `Balances` and `System` pallets.
+pub async fn test_vesting_state<Runtime, Block>(mode: Mode<Block>)
+where
+	Runtime: pallet_vesting::Config<Currency = pallet_balances::Pallet<Runtime>>
+		+ pallet_balances::Config,
+	Block: BlockT,
+{
+	let mut ext = Builder::<Block>::new()
+		.mode(mode)
+		.build()
+		.await
+		.expect("Can't build the remote externalities. Is the node running?");
+	ext.execute_with(|| {
+		migrate::<Runtime>().expect("Vesting migrations failed");
+		check_locks::<Runtime>().expect("Vesting locks are inconsistent after migrating");
+		vest_all::<Runtime>().expect("Vesting locks are inconsistent after vesting");
+	});
+}
+
+/// Run the pending migrations of the pallet, along with their `try-runtime` checks.
+pub fn migrate<Runtime: pallet_vesting::Config>() -> Result<(), &'static str> {
+	<pallet_vesting::Pallet<Runtime> as OnRuntimeUpgrade>::pre_upgrade()?;
+	let weight = <pallet_vesting::Pallet<Runtime> as OnRuntimeUpgrade>::on_runtime_upgrade();
+	log::info!(target: LOG_TARGET, "migrations took {} weight", weight);
+	<pallet_vesting::Pallet<Runtime> as OnRuntimeUpgrade>::post_upgrade()
+}
+
+/// Check that the vesting lock of every vesting account is the amount its lifetime totals record
+/// as locked, and that no account which isn't vesting holds a vesting lock.
+///
+/// Returns the sum of the vesting locks.
+pub fn check_locks<Runtime>() -> Result<Runtime::Balance, &'static str>
+where
+	Runtime: pallet_vesting::Config<Currency = pallet_balances::Pallet<Runtime>>
+		+ pallet_balances::Config,
+{
+	let mut accounts = 0u32;
+	let mut locked = Runtime::Balance::zero();
+	for who in Vesting::<Runtime>::iter_keys() {
+		let lock = vesting_lock::<Runtime>(&who);
+		if lock != LifetimeTotals::<Runtime>::get(&who).locked() {
+			log::error!(
+				target: LOG_TARGET,
+				"vesting lock of {:?} is {:?}, but its totals record {:?}",
+				who,
+				lock,
+				LifetimeTotals::<Runtime>::get(&who),
+			);
+			return Err("Vesting lock does not match the lifetime totals")
+		}
+		accounts += 1;
+		locked = locked.saturating_add(lock);
+	}
+
+	for (who, locks) in pallet_balances::Locks::<Runtime>::iter() {
+		if locks.iter().any(|l| l.id == VESTING_ID) && !Vesting::<Runtime>::contains_key(&who) {
+			log::error!(target: LOG_TARGET, "{:?} holds a vesting lock without vesting", who);
+			return Err("Vesting lock left on an account which is not vesting")
+		}
+	}
+
+	log::info!(target: LOG_TARGET, "{} vesting accounts lock {:?}", accounts, locked);
+	Ok(locked)
+}
+
+/// Replay `vest` for every vesting account, checking that each lock is updated to the amount
+/// still locked under the account's schedule.
+///
+/// Accounts which may not vest yet, e.g. as they have not accepted the terms, are skipped.
+pub fn vest_all<Runtime>() -> Result<(), &'static str>
+where
+	Runtime: pallet_vesting::Config<Currency = pallet_balances::Pallet<Runtime>>
+		+ pallet_balances::Config,
+{
+	let before = check_locks::<Runtime>()?;
+	let (mut vested, mut completed, mut skipped) = (0u32, 0u32, 0u32);
+	for who in Vesting::<Runtime>::iter_keys().collect::<Vec<_>>() {
+		let origin = frame_system::RawOrigin::Signed(who.clone()).into();
+		if let Err(e) = pallet_vesting::Pallet::<Runtime>::vest(origin) {
+			log::debug!(target: LOG_TARGET, "{:?} can't vest: {:?}", who, e.error);
+			skipped += 1;
+			continue
+		}
+
+		let lock = vesting_lock::<Runtime>(&who);
+		let free = pallet_balances::Pallet::<Runtime>::free_balance(&who);
+		match pallet_vesting::Pallet::<Runtime>::vesting_balance(&who) {
+			None if lock.is_zero() => completed += 1,
+			Some(unvested) if unvested == lock.min(free) => vested += 1,
+			unvested => {
+				log::error!(
+					target: LOG_TARGET,
+					"vesting lock of {:?} is {:?} after vesting, but {:?} is unvested",
+					who,
+					lock,
+					unvested,
+				);
+				return Err("Vesting lock was not updated to the unvested balance")
+			},
+		}
+	}
+
+	let after = check_locks::<Runtime>()?;
+	log::info!(
+		target: LOG_TARGET,
+		"vested {} accounts and completed {}, skipping {}; {:?} unlocked",
+		vested,
+		completed,
+		skipped,
+		before.saturating_sub(after),
+	);
+	Ok(())
+}
+
+/// The amount of `who`'s balance under the vesting lock.
+fn vesting_lock<Runtime: pallet_balances::Config>(who: &Runtime::AccountId) -> Runtime::Balance {
+	pallet_balances::Pallet::<Runtime>::locks(who)
+		.iter()
+		.find(|l| l.id == VESTING_ID)
+		.map_or_else(Zero::zero, |l| l.amount)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use remote_externalities::OnlineConfig;
+
+	#[tokio::test]
+	#[ignore]
+	async fn node_runtime_vesting_state_is_consistent() {
+		let transport = std::env::var("REMOTE_TESTS")
+			.expect("Set REMOTE_TESTS to the websocket URL of a node to test against");
+		test_vesting_state::<node_runtime::Runtime, node_runtime::Block>(Mode::Online(
+			OnlineConfig {
+				transport: transport.into(),
+				modules: vec!["Vesting".to_owned(), "Balances".to_owned(), "System".to_owned()],
+				..Default::default()
+			},
+		))
+		.await;
+	}
+}
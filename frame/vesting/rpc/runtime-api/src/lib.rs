@@ -0,0 +1,86 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for vesting pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+pub use pallet_vesting::{ScheduleStatus, VestingInfo, VestingSummary};
+
+sp_api::decl_runtime_apis! {
+	pub trait VestingApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// The amount of `who`'s current free balance which will not be locked by vesting at block
+		/// `at`, assuming the account is vested by then.
+		fn unlockable_at(who: AccountId, at: BlockNumber) -> Balance;
+
+		/// The next block at which the amount locked by `who`'s vesting schedule decreases, and
+		/// the amount by which it does, if it will vest any further.
+		fn next_unlock_block(who: AccountId) -> Option<(BlockNumber, Balance)>;
+
+		/// The breakpoints of the amount locked by `who`'s vesting schedule from the current block
+		/// until block `until`, between which it decreases linearly.
+		fn vesting_timeline(who: AccountId, until: BlockNumber) -> Vec<(BlockNumber, Balance)>;
+
+		/// The total amount which is still unvested across up to `limit` vesting accounts, in
+		/// storage order, starting after the account `start_after` or else from the first, and
+		/// the account to start the next page after, if any are left.
+		fn total_unvested(
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> (Balance, Option<AccountId>);
+
+		/// The vesting summary of each of `accounts`, in the same order.
+		fn vesting_summaries(
+			accounts: Vec<AccountId>,
+		) -> Vec<Option<VestingSummary<Balance, BlockNumber>>>;
+
+		/// The summaries of up to `limit` vesting accounts, in storage order, starting after the
+		/// account `start_after` or else from the first.
+		fn vesting_accounts(
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<(AccountId, VestingSummary<Balance, BlockNumber>)>;
+
+		/// The status of `who`'s vesting schedule as of the current block, if it has one.
+		fn schedule_status(who: AccountId) -> Option<ScheduleStatus<Balance, BlockNumber>>;
+
+		/// The amount still locked by `who`'s vesting and the block by which it is fully vested.
+		fn aggregate_schedule(who: AccountId) -> Option<(Balance, BlockNumber)>;
+
+		/// The vesting schedules funded by `source` by vested transfer which are still in place,
+		/// along with their accounts.
+		fn funded_schedules(source: AccountId) -> Vec<(AccountId, VestingInfo<Balance, BlockNumber>)>;
+
+		/// The number of vesting schedules `who` can currently receive by vested transfer.
+		fn remaining_schedule_slots(who: AccountId) -> u32;
+
+		/// Whether a vested transfer of `schedule` to `target` would be accepted, short of the
+		/// sender's balance covering it and the deposit.
+		fn validate_vested_transfer(
+			target: AccountId,
+			schedule: VestingInfo<Balance, BlockNumber>,
+		) -> Result<(), DispatchError>;
+	}
+}
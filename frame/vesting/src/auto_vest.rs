@@ -0,0 +1,97 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A signed extension unlocking the vested funds of the signer before their call is dispatched.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Currency, Get},
+	weights::{DispatchInfo, GetDispatchInfo},
+};
+use sp_runtime::{
+	traits::{DispatchInfoOf, Dispatchable, Saturating, SignedExtension},
+	transaction_validity::TransactionValidityError,
+};
+use sp_std::marker::PhantomData;
+
+use crate::{BalanceNeeded, Config, Pallet};
+
+/// Unlock any vested funds of the signer before dispatching their call.
+///
+/// The signer's lock is only updated when their free balance left over by the vesting lock falls
+/// short of what `Config::BalanceNeeded` says their call needs, and some of their funds have vested
+/// since the lock was last updated. This spares users from having to submit `vest` before they
+/// can spend their vested funds.
+///
+/// The weight of the check, and of vesting when it happens, is added to the block weight.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct AutoVest<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> AutoVest<T> {
+	/// Create a new `AutoVest` extension.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for AutoVest<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for AutoVest<T> {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "AutoVest")
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for AutoVest<T> where
+	T::Call: Dispatchable<Info=DispatchInfo>
+{
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+	const IDENTIFIER: &'static str = "AutoVest";
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> { Ok(()) }
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<(), TransactionValidityError> {
+		// Free balance and lifetime totals.
+		let mut weight = T::DbWeight::get().reads(2);
+		let usable = T::Currency::free_balance(who)
+			.saturating_sub(Pallet::<T>::lifetime_totals(who).locked());
+		if usable < T::BalanceNeeded::balance_needed(who, call) && Pallet::<T>::can_vest(who) {
+			let max = crate::Call::<T>::vest().get_dispatch_info().weight;
+			// Failing to vest must not invalidate the transaction.
+			let post_info = match Pallet::<T>::do_vest(who.clone()) {
+				Ok(post_info) => post_info,
+				Err(e) => e.post_info,
+			};
+			weight = weight.saturating_add(post_info.actual_weight.unwrap_or(max));
+		}
+		frame_system::Pallet::<T>::register_extra_weight_unchecked(weight, info.class);
+		Ok(())
+	}
+}
@@ -21,9 +21,11 @@
 
 use super::*;
 
+use frame_support::traits::UnfilteredDispatchable;
 use frame_system::{RawOrigin, Pallet as System};
 use frame_benchmarking::{benchmarks, account, whitelisted_caller, impl_benchmark_test_suite};
 use sp_runtime::traits::Bounded;
+use sp_std::convert::TryFrom;
 
 use crate::Pallet as Vesting;
 
@@ -31,6 +33,13 @@ const SEED: u32 = 0;
 
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	let events = System::<T>::events();
+	let system_event: <T as frame_system::Config>::Event = generic_event.into();
+	let frame_system::EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
 fn add_locks<T: Config>(who: &T::AccountId, n: u8) {
 	for id in 0..n {
 		let lock_id = [id; 8];
@@ -40,7 +49,16 @@ fn add_locks<T: Config>(who: &T::AccountId, n: u8) {
 	}
 }
 
-fn add_vesting_schedule<T: Config>(who: &T::AccountId) -> Result<(), &'static str> {
+/// An origin which may make vested transfers, along with the account it makes them from, funded.
+fn funded_transferrer<T: Config>() -> (T::Origin, T::AccountId) {
+	let origin = T::VestedTransferOrigin::successful_origin();
+	let who = T::VestedTransferOrigin::ensure_origin(origin.clone())
+		.expect("`successful_origin` is admitted by the origin; qed");
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value());
+	(origin, who)
+}
+
+fn add_schedule<T: Config>(who: &T::AccountId) -> Result<(), &'static str> {
 	let locked = 100u32;
 	let per_block = 10u32;
 	let starting_block = 1u32;
@@ -57,6 +75,86 @@ fn add_vesting_schedule<T: Config>(who: &T::AccountId) -> Result<(), &'static st
 	Ok(())
 }
 
+/// Fill every table which is cleared once `who`'s schedule completes, and have `who` withhold
+/// some of the funds it unlocks, for the worst case of vesting.
+fn add_schedule_data<T: Config>(who: &T::AccountId) -> Result<(), &'static str> {
+	let other: T::AccountId = account("other", 1, SEED);
+	T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value() / 2u32.into());
+	let deposit = T::Currency::minimum_balance();
+	T::Currency::reserve_named(&T::ReserveId::get(), &other, deposit)?;
+	ScheduleDeposits::<T>::insert(who, (other.clone(), deposit));
+	Withholding::<T>::insert(who, (other.clone(), Perbill::from_percent(10)));
+	ContingentBeneficiary::<T>::insert(who, other.clone());
+	let metadata = vec![0u8; T::MaxMetadataLen::get() as usize];
+	let metadata = BoundedVec::try_from(metadata).map_err(|_| "Metadata is too long")?;
+	ScheduleMetadata::<T>::insert(who, metadata);
+	PlanOf::<T>::insert(who, 0);
+	PlanMembers::<T>::insert(0, who, ());
+	FundedBy::<T>::insert(who, other.clone());
+	FundedSchedules::<T>::insert(&other, who, ());
+	SelfImposed::<T>::insert(who, ());
+	let at: T::BlockNumber = 100u32.into();
+	AutoVestAt::<T>::insert(who, at);
+	AutoVestQueue::<T>::mutate(at, |queue| queue.try_push(who.clone()))
+		.map_err(|_| "Auto-vest queue is full")?;
+	BonusPaidUntil::<T>::insert(who, T::BlockNumber::zero());
+	if let Some(schedule) = Vesting::<T>::vesting(who) {
+		for _ in 0..T::MaxArchived::get() {
+			Vesting::<T>::archive(who, schedule, Zero::zero());
+		}
+	}
+	Ok(())
+}
+
+fn add_standing_order<T: Config>(
+) -> Result<(T::AccountId, <T::Lookup as StaticLookup>::Source), &'static str> {
+	let (origin, funder) = funded_transferrer::<T>();
+	let target: T::AccountId = account("target", 0, SEED);
+	let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target);
+	Call::<T>::create_standing_order(
+		target_lookup.clone(),
+		T::MinVestedTransfer::get(),
+		10u32.into(),
+		0u32.into(),
+		10u32.into(),
+		12,
+	).dispatch_bypass_filter(origin)?;
+	Ok((funder, target_lookup))
+}
+
+fn add_scheduled_transfer<T: Config>() -> Result<T::AccountId, &'static str> {
+	let (origin, source) = funded_transferrer::<T>();
+	let target: T::AccountId = account("target", 0, SEED);
+	let vesting_schedule = VestingInfo {
+		locked: T::MinVestedTransfer::get(),
+		per_block: 10u32.into(),
+		starting_block: 1u32.into(),
+	};
+	Call::<T>::schedule_vested_transfer(
+		10u32.into(),
+		T::Lookup::unlookup(target),
+		vesting_schedule,
+	).dispatch_bypass_filter(origin)?;
+	Ok(source)
+}
+
+fn add_stream<T: Config>() -> Result<T::AccountId, &'static str> {
+	let sender: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&sender, BalanceOf::<T>::max_value());
+	let target: T::AccountId = account("target", 0, SEED);
+	// The recipient must exist to receive streamed funds.
+	T::Currency::make_free_balance_be(&target, T::Currency::minimum_balance());
+	System::<T>::set_block_number(0u32.into());
+	Vesting::<T>::create_stream(
+		RawOrigin::Signed(sender).into(),
+		T::Lookup::unlookup(target.clone()),
+		100u32.into(),
+		10u32.into(),
+		1u32.into(),
+	)?;
+	Ok(target)
+}
+
 benchmarks! {
 	vest_locked {
 		let l in 0 .. MaxLocksOf::<T>::get();
@@ -64,7 +162,8 @@ benchmarks! {
 		let caller = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		add_locks::<T>(&caller, l as u8);
-		add_vesting_schedule::<T>(&caller)?;
+		add_schedule::<T>(&caller)?;
+		add_schedule_data::<T>(&caller)?;
 		// At block zero, everything is vested.
 		System::<T>::set_block_number(T::BlockNumber::zero());
 		assert_eq!(
@@ -88,7 +187,8 @@ benchmarks! {
 		let caller = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		add_locks::<T>(&caller, l as u8);
-		add_vesting_schedule::<T>(&caller)?;
+		add_schedule::<T>(&caller)?;
+		add_schedule_data::<T>(&caller)?;
 		// At block 20, everything is unvested.
 		System::<T>::set_block_number(20u32.into());
 		assert_eq!(
@@ -104,6 +204,7 @@ benchmarks! {
 			None,
 			"Vesting schedule was not removed",
 		);
+		assert!(!ScheduleDeposits::<T>::contains_key(&caller), "Schedule data was not cleared");
 	}
 
 	vest_other_locked {
@@ -113,7 +214,8 @@ benchmarks! {
 		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
 		T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value());
 		add_locks::<T>(&other, l as u8);
-		add_vesting_schedule::<T>(&other)?;
+		add_schedule::<T>(&other)?;
+		add_schedule_data::<T>(&other)?;
 		// At block zero, everything is vested.
 		System::<T>::set_block_number(T::BlockNumber::zero());
 		assert_eq!(
@@ -123,6 +225,11 @@ benchmarks! {
 		);
 
 		let caller: T::AccountId = whitelisted_caller();
+		// Approve the caller so that `vest_other` is allowed even if permissioned.
+		Vesting::<T>::approve_vester(
+			RawOrigin::Signed(other.clone()).into(),
+			T::Lookup::unlookup(caller.clone()),
+		)?;
 	}: vest_other(RawOrigin::Signed(caller.clone()), other_lookup)
 	verify {
 		// Nothing happened since everything is still vested.
@@ -140,7 +247,8 @@ benchmarks! {
 		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
 		T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value());
 		add_locks::<T>(&other, l as u8);
-		add_vesting_schedule::<T>(&other)?;
+		add_schedule::<T>(&other)?;
+		add_schedule_data::<T>(&other)?;
 		// At block 20, everything is unvested.
 		System::<T>::set_block_number(20u32.into());
 		assert_eq!(
@@ -150,6 +258,11 @@ benchmarks! {
 		);
 
 		let caller: T::AccountId = whitelisted_caller();
+		// Approve the caller so that `vest_other` is allowed even if permissioned.
+		Vesting::<T>::approve_vester(
+			RawOrigin::Signed(other.clone()).into(),
+			T::Lookup::unlookup(caller.clone()),
+		)?;
 	}: vest_other(RawOrigin::Signed(caller.clone()), other_lookup)
 	verify {
 		// Vesting schedule is removed!
@@ -158,13 +271,13 @@ benchmarks! {
 			None,
 			"Vesting schedule was not removed",
 		);
+		assert!(!ScheduleDeposits::<T>::contains_key(&other), "Schedule data was not cleared");
 	}
 
 	vested_transfer {
 		let l in 0 .. MaxLocksOf::<T>::get();
 
-		let caller: T::AccountId = whitelisted_caller();
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let (origin, _) = funded_transferrer::<T>();
 		let target: T::AccountId = account("target", 0, SEED);
 		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
 		// Give target existing locks
@@ -177,7 +290,8 @@ benchmarks! {
 			per_block: 10u32.into(),
 			starting_block: 1u32.into(),
 		};
-	}: _(RawOrigin::Signed(caller), target_lookup, vesting_schedule)
+		let call = Call::<T>::vested_transfer(target_lookup, vesting_schedule);
+	}: { call.dispatch_bypass_filter(origin)? }
 	verify {
 		assert_eq!(
 			T::MinVestedTransfer::get(),
@@ -209,7 +323,9 @@ benchmarks! {
 			per_block: 10u32.into(),
 			starting_block: 1u32.into(),
 		};
-	}: _(RawOrigin::Root, source_lookup, target_lookup, vesting_schedule)
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::force_vested_transfer(source_lookup, target_lookup, vesting_schedule);
+	}: { call.dispatch_bypass_filter(origin)? }
 	verify {
 		assert_eq!(
 			T::MinVestedTransfer::get(),
@@ -222,6 +338,569 @@ benchmarks! {
 			"Lock not created",
 		);
 	}
+
+	remove_orphaned_vesting {
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		// A schedule left behind on an account without any funds.
+		crate::Vesting::<T>::insert(&target, VersionedVestingInfo::V1(VestingInfo {
+			locked: T::MinVestedTransfer::get(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+		}));
+
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), target_lookup)
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&target), None, "Vesting schedule was not removed");
+	}
+
+	schedule_vested_transfer {
+		let (origin, caller) = funded_transferrer::<T>();
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+
+		let vesting_schedule = VestingInfo {
+			locked: T::MinVestedTransfer::get(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+		};
+		let when: T::BlockNumber = 10u32.into();
+		let call = Call::<T>::schedule_vested_transfer(when, target_lookup, vesting_schedule);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_last_event::<T>(
+			Event::<T>::VestedTransferScheduled { source: caller, index: 0, target, when }.into(),
+		);
+	}
+
+	cancel_scheduled_transfer {
+		let caller = add_scheduled_transfer::<T>()?;
+	}: _(RawOrigin::Signed(caller.clone()), 0)
+	verify {
+		assert!(
+			Vesting::<T>::scheduled_transfers(&caller, 0).is_none(),
+			"Scheduled transfer not removed",
+		);
+	}
+
+	execute_scheduled_transfer {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let caller = add_scheduled_transfer::<T>()?;
+		let target: T::AccountId = account("target", 0, SEED);
+		// Give target existing locks
+		add_locks::<T>(&target, l as u8);
+	}: _(RawOrigin::Root, caller, 0)
+	verify {
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&target),
+			Some(T::MinVestedTransfer::get()),
+			"Lock not created",
+		);
+	}
+
+	require_terms {
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		let statement_hash = T::Hash::default();
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::require_terms(target_lookup, statement_hash);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::pending_terms(&target), Some(statement_hash), "Terms not required");
+	}
+
+	clear_terms {
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		PendingTerms::<T>::insert(&target, T::Hash::default());
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::clear_terms(target_lookup);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::pending_terms(&target), None, "Terms not cleared");
+	}
+
+	create_standing_order {
+		let (origin, caller) = funded_transferrer::<T>();
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		let call = Call::<T>::create_standing_order(
+			target_lookup,
+			T::MinVestedTransfer::get(),
+			10u32.into(),
+			0u32.into(),
+			10u32.into(),
+			12,
+		);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_last_event::<T>(
+			Event::<T>::StandingOrderCreated { funder: caller, index: 0, target }.into(),
+		);
+	}
+
+	modify_standing_order {
+		let (caller, target_lookup) = add_standing_order::<T>()?;
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		0,
+		target_lookup,
+		T::MinVestedTransfer::get(),
+		20u32.into(),
+		5u32.into()
+	)
+	verify {
+		assert_last_event::<T>(
+			Event::<T>::StandingOrderModified { funder: caller, index: 0 }.into(),
+		);
+	}
+
+	cancel_standing_order {
+		let (caller, _) = add_standing_order::<T>()?;
+	}: _(RawOrigin::Signed(caller.clone()), 0)
+	verify {
+		assert!(Vesting::<T>::standing_orders(&caller, 0).is_none(), "Standing order not removed");
+	}
+
+	execute_standing_order {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let (caller, _) = add_standing_order::<T>()?;
+		let target: T::AccountId = account("target", 0, SEED);
+		// Give target existing locks
+		add_locks::<T>(&target, l as u8);
+	}: _(RawOrigin::Root, caller, 0)
+	verify {
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&target),
+			Some(T::MinVestedTransfer::get()),
+			"Lock not created",
+		);
+	}
+
+	create_stream {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller.clone()), target_lookup, 100u32.into(), 10u32.into(), 1u32.into())
+	verify {
+		assert_last_event::<T>(
+			Event::<T>::StreamCreated { index: 0, sender: caller, recipient: target }.into(),
+		);
+	}
+
+	withdraw_from_stream {
+		let target = add_stream::<T>()?;
+		System::<T>::set_block_number(5u32.into());
+	}: _(RawOrigin::Signed(target), 0)
+	verify {
+		assert_last_event::<T>(
+			Event::<T>::StreamWithdrawn { index: 0, amount: 40u32.into() }.into(),
+		);
+	}
+
+	cancel_stream {
+		add_stream::<T>()?;
+		let caller: T::AccountId = whitelisted_caller();
+		System::<T>::set_block_number(5u32.into());
+	}: _(RawOrigin::Signed(caller), 0)
+	verify {
+		assert_last_event::<T>(
+			Event::<T>::StreamCancelled {
+				index: 0,
+				paid_out: 40u32.into(),
+				refunded: 60u32.into(),
+			}.into(),
+		);
+	}
+
+	create_vesting_plan {
+		let plan = VestingPlan { cliff: 10u32.into(), duration: 100u32.into(), revocable: true };
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::create_vesting_plan(plan);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::vesting_plans(0), Some(plan), "Plan not created");
+	}
+
+	vested_transfer_with_plan {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let (origin, _) = funded_transferrer::<T>();
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		// Give target existing locks
+		add_locks::<T>(&target, l as u8);
+
+		let plan = VestingPlan { cliff: 10u32.into(), duration: 100u32.into(), revocable: true };
+		Call::<T>::create_vesting_plan(plan).dispatch_bypass_filter(T::ForceOrigin::successful_origin())?;
+		let call = Call::<T>::vested_transfer_with_plan(target_lookup, 0, T::MinVestedTransfer::get());
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&target),
+			Some(T::MinVestedTransfer::get()),
+			"Lock not created",
+		);
+		assert_eq!(Vesting::<T>::plan_of(&target), Some(0), "Plan not recorded");
+	}
+
+	vested_transfer_with_metadata {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let (origin, _) = funded_transferrer::<T>();
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		// Give target existing locks
+		add_locks::<T>(&target, l as u8);
+
+		let schedule = VestingInfo {
+			locked: T::MinVestedTransfer::get(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+		};
+		let metadata: BoundedVec<u8, T::MaxMetadataLen> =
+			vec![0u8; T::MaxMetadataLen::get() as usize].try_into().map_err(|_| "Too long")?;
+		let call =
+			Call::<T>::vested_transfer_with_metadata(target_lookup, schedule, metadata.clone());
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::schedule_metadata(&target), Some(metadata), "Not recorded");
+	}
+
+	revoke_vesting_plan {
+		let n in 0 .. 100;
+
+		let (origin, _) = funded_transferrer::<T>();
+		let plan = VestingPlan { cliff: 10u32.into(), duration: 100u32.into(), revocable: true };
+		Call::<T>::create_vesting_plan(plan).dispatch_bypass_filter(T::ForceOrigin::successful_origin())?;
+		for i in 0 .. n {
+			let target: T::AccountId = account("target", i, SEED);
+			Call::<T>::vested_transfer_with_plan(
+				T::Lookup::unlookup(target),
+				0,
+				T::MinVestedTransfer::get(),
+			).dispatch_bypass_filter(origin.clone())?;
+		}
+		let destination: T::AccountId = account("destination", 0, SEED);
+		let destination_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(destination);
+		let force_origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::revoke_vesting_plan(0, destination_lookup, n);
+	}: { call.dispatch_bypass_filter(force_origin)? }
+	verify {
+		assert_last_event::<T>(Event::<T>::VestingPlanRevoked { plan: 0 }.into());
+	}
+
+	set_contingent_beneficiary {
+		let target: T::AccountId = account("target", 0, SEED);
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_schedule::<T>(&target)?;
+		let contingent: T::AccountId = account("contingent", 0, SEED);
+		let contingent_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(contingent.clone());
+	}: _(RawOrigin::Signed(target.clone()), Some(contingent_lookup))
+	verify {
+		assert_eq!(Vesting::<T>::contingent_beneficiary(&target), Some(contingent), "Contingent not set");
+	}
+
+	set_withholding {
+		let target: T::AccountId = account("target", 0, SEED);
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_schedule::<T>(&target)?;
+		let destination: T::AccountId = account("destination", 0, SEED);
+		let destination_lookup = T::Lookup::unlookup(destination.clone());
+		let rate = Perbill::from_percent(20);
+	}: _(RawOrigin::Signed(target.clone()), Some((destination_lookup, rate)))
+	verify {
+		assert_eq!(Vesting::<T>::withholding(&target), Some((destination, rate)), "Not set");
+	}
+
+	claim_as_contingent {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let target: T::AccountId = account("target", 0, SEED);
+		add_locks::<T>(&target, l as u8);
+		// Leave enough to cover the existing locks once the schedule's funds are moved.
+		T::Currency::make_free_balance_be(&target, T::MinVestedTransfer::get() * 2u32.into());
+		Vesting::<T>::add_vesting_schedule(
+			&target,
+			T::MinVestedTransfer::get(),
+			T::MinVestedTransfer::get(),
+			1u32.into(),
+		)?;
+		Vesting::<T>::set_contingent_beneficiary(
+			RawOrigin::Signed(target.clone()).into(),
+			Some(T::Lookup::unlookup(caller.clone())),
+		)?;
+		frame_system::Pallet::<T>::set_block_number(T::ContingencyPeriod::get() + 2u32.into());
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller), target_lookup)
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&target), None, "Schedule not completed");
+	}
+
+	sweep {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let target: T::AccountId = account("target", 0, SEED);
+		add_locks::<T>(&target, l as u8);
+		// Leave enough to cover the existing locks once the schedule's funds are moved.
+		T::Currency::make_free_balance_be(&target, T::MinVestedTransfer::get() * 2u32.into());
+		Vesting::<T>::add_vesting_schedule(
+			&target,
+			T::MinVestedTransfer::get(),
+			T::MinVestedTransfer::get(),
+			1u32.into(),
+		)?;
+		let delay = T::SweepDelay::get().ok_or("Sweeping disabled")?;
+		frame_system::Pallet::<T>::set_block_number(delay + 2u32.into());
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller), target_lookup)
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&target), None, "Schedule not swept");
+	}
+
+	approve_vester {
+		let caller: T::AccountId = whitelisted_caller();
+		let vester: T::AccountId = account("vester", 0, SEED);
+		let vester_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(vester.clone());
+	}: _(RawOrigin::Signed(caller.clone()), vester_lookup)
+	verify {
+		assert_last_event::<T>(Event::<T>::VesterApproved { account: caller, vester }.into());
+	}
+
+	revoke_vester {
+		let caller: T::AccountId = whitelisted_caller();
+		let vester: T::AccountId = account("vester", 0, SEED);
+		let vester_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(vester.clone());
+		Vesting::<T>::approve_vester(RawOrigin::Signed(caller.clone()).into(), vester_lookup.clone())?;
+	}: _(RawOrigin::Signed(caller.clone()), vester_lookup)
+	verify {
+		assert_last_event::<T>(Event::<T>::VesterRevoked { account: caller, vester }.into());
+	}
+
+	pause {
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::pause();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Vesting::<T>::paused(), "Not paused");
+	}
+
+	unpause {
+		let origin = T::ForceOrigin::successful_origin();
+		Call::<T>::pause().dispatch_bypass_filter(origin.clone())?;
+		let call = Call::<T>::unpause();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(!Vesting::<T>::paused(), "Still paused");
+	}
+
+	freeze_progression {
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::freeze_progression();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Vesting::<T>::progression_frozen_since().is_some(), "Not frozen");
+	}
+
+	resume_progression {
+		let origin = T::ForceOrigin::successful_origin();
+		Call::<T>::freeze_progression().dispatch_bypass_filter(origin.clone())?;
+		let call = Call::<T>::resume_progression();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Vesting::<T>::progression_frozen_since().is_none(), "Still frozen");
+	}
+
+	set_min_vested_transfer {
+		let origin = T::ForceOrigin::successful_origin();
+		let amount = T::MinVestedTransfer::get() * 2u32.into();
+		let call = Call::<T>::set_min_vested_transfer(amount);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::min_vested_transfer(), amount, "Minimum not set");
+	}
+
+	export_schedule {
+		let l in 0 .. MaxLocksOf::<T>::get() - 1;
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+		add_schedule::<T>(&target)?;
+
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::export_schedule(target_lookup);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Vesting::<T>::vesting(&target).is_none(), "Schedule not exported");
+	}
+
+	import_schedule {
+		let l in 0 .. MaxLocksOf::<T>::get() - 1;
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+
+		let schedule = VestingInfo {
+			locked: T::MinVestedTransfer::get(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+		};
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::import_schedule(target_lookup, schedule);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&target), Some(schedule), "Schedule not imported");
+	}
+
+	force_apply_vesting {
+		let l in 0 .. MaxLocksOf::<T>::get() - 1;
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+
+		let schedule = VestingInfo {
+			locked: T::MinVestedTransfer::get(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+		};
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::force_apply_vesting(target_lookup, schedule);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&target), Some(schedule), "Schedule not applied");
+	}
+
+	vest_own_balance {
+		let l in 0 .. MaxLocksOf::<T>::get() - 1;
+
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_locks::<T>(&caller, l as u8);
+
+		let schedule = VestingInfo {
+			locked: T::MinVestedTransfer::get(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+		};
+	}: _(RawOrigin::Signed(caller.clone()), schedule)
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&caller), Some(schedule), "Schedule not imposed");
+	}
+
+	exit_early {
+		let l in 0 .. MaxLocksOf::<T>::get() - 1;
+
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_locks::<T>(&caller, l as u8);
+
+		let schedule = VestingInfo {
+			locked: T::MinVestedTransfer::get(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+		};
+		Vesting::<T>::vest_own_balance(RawOrigin::Signed(caller.clone()).into(), schedule)?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&caller), None, "Schedule not exited");
+	}
+
+	set_claims_root {
+		let origin = T::ForceOrigin::successful_origin();
+		let root = T::Hashing::hash_of(&0u32);
+		let call = Call::<T>::set_claims_root(Some(root));
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::claims_root(), Some(root), "Root not set");
+	}
+
+	claim_vesting {
+		let p in 0 .. T::MaxClaimProofLength::get();
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let schedule = VestingInfo {
+			locked: T::MinVestedTransfer::get(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+		};
+		let proof: Vec<T::Hash> = (0..p).map(|i| T::Hashing::hash_of(&i)).collect();
+		let leaf = Vesting::<T>::claim_leaf(&target, &schedule);
+		ClaimsRoot::<T>::put(Vesting::<T>::claim_root_of(leaf, &proof));
+
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), target.clone(), schedule, proof)
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&target), Some(schedule), "Allocation not claimed");
+	}
+
+	add_vesting_schedule {
+		let l in 0 .. MaxLocksOf::<T>::get() - 1;
+
+		let target: T::AccountId = account("target", 0, SEED);
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+		let locked = T::MinVestedTransfer::get();
+	}: {
+		<Vesting<T> as VestingSchedule<_>>::add_vesting_schedule(
+			&target,
+			locked,
+			10u32.into(),
+			1u32.into(),
+		)?;
+	}
+	verify {
+		assert_eq!(Vesting::<T>::vesting_balance(&target), Some(locked), "Schedule not added");
+	}
+
+	remove_vesting_schedule {
+		let l in 0 .. MaxLocksOf::<T>::get() - 1;
+
+		let target: T::AccountId = account("target", 0, SEED);
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+		add_schedule::<T>(&target)?;
+	}: {
+		<Vesting<T> as VestingSchedule<_>>::remove_vesting_schedule(&target);
+	}
+	verify {
+		assert_eq!(Vesting::<T>::vesting(&target), None, "Schedule not removed");
+	}
+
+	register_auto_vest {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_schedule::<T>(&caller)?;
+		// Leave a single slot in the queue.
+		let at = Vesting::<T>::auto_vest_block(&caller).ok_or("No schedule")?;
+		for i in 1 .. T::MaxAutoVestsPerBlock::get() {
+			AutoVestQueue::<T>::try_append(at, account::<T::AccountId>("queued", i, SEED))
+				.map_err(|_| "Queue full")?;
+		}
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Vesting::<T>::auto_vest_at(&caller), Some(at), "Not registered");
+	}
+
+	set_unlock_multiplier {
+		let origin = T::UnlockMultiplierOrigin::successful_origin();
+		let multiplier = FixedU128::saturating_from_rational(3, 2);
+		let call = Call::<T>::set_unlock_multiplier(multiplier);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::unlock_multiplier(), multiplier, "Multiplier not set");
+	}
 }
 
 impl_benchmark_test_suite!(
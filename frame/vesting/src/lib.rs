@@ -34,7 +34,7 @@
 //!
 //! ## Interface
 //!
-//! This pallet implements the `VestingSchedule` trait.
+//! This pallet implements the `VestingSchedule`, `VestedTransfer` and `UnvestedBalance` traits.
 //!
 //! ### Dispatchable Functions
 //!
@@ -44,7 +44,13 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod auto_vest;
 mod benchmarking;
+pub mod migrations;
+mod pay;
+mod shadow;
+#[cfg(feature = "std")]
+pub mod test_utils;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -55,58 +61,462 @@ pub mod weights;
 use codec::{Decode, Encode};
 use frame_support::{
 	ensure,
+	ReversibleStorageHasher,
 	pallet_prelude::*,
+	storage::{with_transaction, StoragePrefixedMap},
 	traits::{
-		Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency, VestingSchedule,
+		schedule::{Anon as ScheduleAnon, DispatchTime, LOWEST_PRIORITY},
+		tokens::nonfungibles::{self, Inspect as _, Mutate as _},
+		BalanceStatus, Contains, Currency, EnsureOrigin, ExistenceRequirement, Filter, Get,
+		IsSubType, LockIdentifier, LockableCurrency, NamedReservableCurrency, OnKilledAccount,
+		OnUnbalanced, UnvestedBalance, VestedTransfer, VestingSchedule,
 		WithdrawReasons,
 	},
 };
-use frame_system::{ensure_root, ensure_signed, pallet_prelude::*};
+use frame_system::{
+	ensure_none, ensure_root, ensure_signed,
+	offchain::{SendTransactionTypes, SubmitTransaction},
+	pallet_prelude::*,
+};
+pub use auto_vest::AutoVest;
+pub use pay::{Pay, PayByMinting, PayFromAccount, PayVested};
+pub use shadow::ShadowAsset;
 pub use pallet::*;
+pub use pallet_vesting_primitives::{
+	block_number_to_balance, VersionedVestingInfo, VestingInfo, VestingInfoAt, VestingInfoBuilder,
+};
 use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, Convert, MaybeSerializeDeserialize, StaticLookup, Zero},
-	RuntimeDebug,
+	traits::{
+		AtLeast32BitUnsigned, CheckedDiv, Hash as HashT, IdentifyAccount,
+		MaybeSerializeDeserialize, One, Saturating, StaticLookup, UniqueSaturatedFrom,
+		UniqueSaturatedInto, Verify, Zero,
+	},
+	FixedPointNumber, FixedU128, Perbill, RuntimeDebug, TransactionOutcome,
 };
-use sp_std::{fmt::Debug, prelude::*};
+use sp_std::{convert::TryInto, fmt::Debug, marker::PhantomData, prelude::*};
 pub use weights::WeightInfo;
 
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+type ReserveIdentifierOf<T> = <<T as Config>::Currency as NamedReservableCurrency<
+	<T as frame_system::Config>::AccountId,
+>>::ReserveIdentifier;
 type MaxLocksOf<T> =
 	<<T as Config>::Currency as LockableCurrency<<T as frame_system::Config>::AccountId>>::MaxLocks;
 
-const VESTING_ID: LockIdentifier = *b"vesting ";
+type TaskAddressOf<T> = <<T as Config>::Scheduler as ScheduleAnon<
+	<T as frame_system::Config>::BlockNumber,
+	<T as Config>::ScheduledCall,
+	<T as Config>::PalletsOrigin,
+>>::Address;
+type StandingOrderOf<T> = StandingOrder<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	TaskAddressOf<T>,
+>;
+type ScheduledTransferOf<T> = ScheduledTransfer<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	TaskAddressOf<T>,
+>;
 
-/// Struct to encode the vesting schedule of an individual account.
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
-pub struct VestingInfo<Balance, BlockNumber> {
-	/// Locked amount at genesis.
+type PositionClassOf<T> = <<T as Config>::Positions as nonfungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::ClassId;
+type LocationOf<T> = <<T as Config>::Beneficiaries as DeriveBeneficiary<
+	<T as frame_system::Config>::AccountId,
+>>::Location;
+
+/// The identifier of the balance lock placed by vesting.
+pub const VESTING_ID: LockIdentifier = *b"vesting ";
+
+/// A genesis vesting entry, locking all but `liquid` of the account's endowment.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase", deny_unknown_fields))]
+pub struct GenesisVesting<AccountId, BlockNumber, Balance> {
+	/// Account which is vesting.
+	pub who: AccountId,
+	/// Block when the account will start to vest.
+	pub begin: BlockNumber,
+	/// Number of blocks from `begin` until fully vested.
+	pub length: BlockNumber,
+	/// Number of units which can be spent before vesting begins.
+	pub liquid: Balance,
+}
+
+/// A genesis vesting entry, locking all but `liquid` of the account's endowment until it is fully
+/// vested at block `end`.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase", deny_unknown_fields))]
+pub struct GenesisVestingUntil<AccountId, BlockNumber, Balance> {
+	/// Account which is vesting.
+	pub who: AccountId,
+	/// Block when the account will start to vest.
+	pub begin: BlockNumber,
+	/// Block by which the account is fully vested. Must be after `begin`.
+	pub end: BlockNumber,
+	/// Number of units which can be spent before vesting begins.
+	pub liquid: Balance,
+}
+
+/// A genesis vesting entry, locking the fraction `locked` of the account's endowment.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase", deny_unknown_fields))]
+pub struct FractionalGenesisVesting<AccountId, BlockNumber> {
+	/// Account which is vesting.
+	pub who: AccountId,
+	/// Block when the account will start to vest.
+	pub begin: BlockNumber,
+	/// Number of blocks from `begin` until fully vested.
+	pub length: BlockNumber,
+	/// Fraction of the account's endowment which is locked.
+	pub locked: Perbill,
+}
+
+
+/// A standing order making a vested transfer to `target` every `interval` blocks.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct StandingOrder<AccountId, Balance, BlockNumber, TaskAddress> {
+	/// The account receiving the transfers.
+	pub target: AccountId,
+	/// Amount transferred and locked by each transfer.
 	pub locked: Balance,
-	/// Amount that gets unlocked every block after `starting_block`.
+	/// Amount of each transfer that gets unlocked every block once it starts vesting.
+	pub per_block: Balance,
+	/// Number of blocks after each transfer that its funds start vesting.
+	pub delay: BlockNumber,
+	/// Number of blocks between transfers.
+	pub interval: BlockNumber,
+	/// Number of transfers still to be made.
+	pub remaining: u32,
+	/// The scheduler task making the transfers.
+	pub task: TaskAddress,
+}
+
+/// A vested transfer to `target` scheduled to happen at a later block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ScheduledTransfer<AccountId, Balance, BlockNumber, TaskAddress> {
+	/// The account receiving the transfer.
+	pub target: AccountId,
+	/// The vesting schedule attached to the transfer.
+	pub schedule: VestingInfo<Balance, BlockNumber>,
+	/// The scheduler task making the transfer.
+	pub task: TaskAddress,
+}
+
+/// A stream of funds from `sender` to `recipient`, paid out continuously every block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Stream<AccountId, Balance, BlockNumber> {
+	/// The account funding the stream. The funds still to be streamed are reserved from it.
+	pub sender: AccountId,
+	/// The account receiving the stream.
+	pub recipient: AccountId,
+	/// Total amount streamed over the lifetime of the stream.
+	pub amount: Balance,
+	/// Amount streamed to `recipient` every block after `starting_block`.
 	pub per_block: Balance,
-	/// Starting block for unlocking(vesting).
+	/// The block after which funds start streaming.
 	pub starting_block: BlockNumber,
+	/// Amount `recipient` has withdrawn so far.
+	pub withdrawn: Balance,
 }
 
-impl<
-	Balance: AtLeast32BitUnsigned + Copy,
-	BlockNumber: AtLeast32BitUnsigned + Copy,
-> VestingInfo<Balance, BlockNumber> {
-	/// Amount locked at block `n`.
-	pub fn locked_at<
-		BlockNumberToBalance: Convert<BlockNumber, Balance>
-	>(&self, n: BlockNumber) -> Balance {
-		// Number of blocks that count toward vesting
-		// Saturating to 0 when n < starting_block
-		let vested_block_count = n.saturating_sub(self.starting_block);
-		let vested_block_count = BlockNumberToBalance::convert(vested_block_count);
-		// Return amount that is still locked in vesting
-		let maybe_balance = vested_block_count.checked_mul(&self.per_block);
-		if let Some(balance) = maybe_balance {
-			self.locked.saturating_sub(balance)
-		} else {
-			Zero::zero()
-		}
+impl<AccountId, Balance: AtLeast32BitUnsigned + Copy, BlockNumber: AtLeast32BitUnsigned + Copy>
+	Stream<AccountId, Balance, BlockNumber>
+{
+	/// Amount streamed to `recipient` by block `n`, including any amount already withdrawn.
+	pub fn streamed_at(&self, n: BlockNumber) -> Balance {
+		let streamed_block_count: Balance =
+			block_number_to_balance(n.saturating_sub(self.starting_block));
+		streamed_block_count.saturating_mul(self.per_block).min(self.amount)
+	}
+
+	/// Amount `recipient` may withdraw at block `n`.
+	pub fn withdrawable_at(&self, n: BlockNumber) -> Balance {
+		self.streamed_at(n).saturating_sub(self.withdrawn)
+	}
+}
+
+/// How the minimum amount of vested transfers is enforced.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct MinVestedTransferPolicy {
+	/// Whether forced vested transfers are exempt from the minimum.
+	pub exempt_forced: bool,
+	/// Whether the minimum applies to the amount still locked at the time of the transfer rather
+	/// than the amount locked by the schedule as a whole.
+	pub check_remaining: bool,
+	/// Whether a transfer of exactly the minimum is allowed.
+	pub inclusive: bool,
+}
+
+impl Default for MinVestedTransferPolicy {
+	fn default() -> Self {
+		Self { exempt_forced: false, check_remaining: false, inclusive: true }
+	}
+}
+
+/// A template from which vesting schedules are derived, keeping those of a distribution consistent.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingPlan<BlockNumber> {
+	/// Number of blocks after the transfer before anything vests.
+	pub cliff: BlockNumber,
+	/// Number of blocks after the cliff over which the funds vest.
+	pub duration: BlockNumber,
+	/// Whether governance may revoke schedules derived from the plan.
+	pub revocable: bool,
+}
+
+/// Funds vesting to whoever owns the NFT representing the position.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Position<AccountId, Balance, BlockNumber> {
+	/// The account funding the position. The funds still to be paid out are reserved from it.
+	pub funder: AccountId,
+	/// The schedule according to which the funds vest, starting at a reading of the vesting
+	/// clock.
+	pub schedule: VestingInfo<Balance, BlockNumber>,
+	/// Amount paid out so far.
+	pub paid_out: Balance,
+}
+
+/// Handler for when an account's vesting schedule has completed.
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+pub trait OnVestingCompleted<AccountId> {
+	/// The account `who` has become fully vested and no longer has a vesting schedule.
+	fn on_vesting_completed(who: &AccountId);
+}
+
+/// Handler for when vesting has unlocked some of an account's funds.
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+pub trait OnVestUnlocked<AccountId, Balance> {
+	/// `amount` of `who`'s funds have just become transferable through vesting.
+	fn on_vest_unlocked(who: &AccountId, amount: Balance);
+}
+
+/// Handler for changes of the amount of an account's funds locked under vesting.
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+pub trait OnLockedChanged<AccountId, Balance> {
+	/// `locked` of `who`'s funds are now locked under vesting, which is zero once the schedule
+	/// has been removed.
+	fn on_locked_changed(who: &AccountId, locked: Balance);
+}
+
+/// An external condition, e.g. on a price feed or an attestation bridged on-chain, which must be
+/// met before vested funds may be unlocked.
+pub trait UnlockOracle<AccountId, Balance, BlockNumber> {
+	/// Whether `who` may unlock the funds which have vested under `schedule`.
+	fn can_unlock(who: &AccountId, schedule: &VestingInfo<Balance, BlockNumber>) -> bool;
+}
+
+impl<AccountId, Balance, BlockNumber> UnlockOracle<AccountId, Balance, BlockNumber> for () {
+	fn can_unlock(_: &AccountId, _: &VestingInfo<Balance, BlockNumber>) -> bool {
+		true
+	}
+}
+
+/// The balance a call needs to be free in the account of its signer, used by `AutoVest` to tell
+/// whether vested funds must be unlocked before the call is dispatched.
+pub trait BalanceNeeded<AccountId, Call, Balance> {
+	/// The balance `who` needs to be free of locks to dispatch `call`.
+	fn balance_needed(who: &AccountId, call: &Call) -> Balance;
+}
+
+/// Never asks for any balance, so that `AutoVest` leaves every lock as it is.
+impl<AccountId, Call, Balance: Zero> BalanceNeeded<AccountId, Call, Balance> for () {
+	fn balance_needed(_: &AccountId, _: &Call) -> Balance {
+		Zero::zero()
+	}
+}
+
+/// Derives the accounts which vested transfers can target without naming them directly, e.g. the
+/// utility pallet's derivative accounts or the sovereign accounts of parachains.
+pub trait DeriveBeneficiary<AccountId> {
+	/// The location owning a sovereign account, e.g. a parachain id.
+	type Location: Parameter;
+
+	/// The derivative account of `owner` with the given `index`, if derivative accounts are
+	/// supported.
+	fn derivative_account(owner: &AccountId, index: u16) -> Option<AccountId>;
+
+	/// The sovereign account of `location`, if it has one.
+	fn sovereign_account(location: &Self::Location) -> Option<AccountId>;
+}
+
+/// Derives no accounts, so that vested transfers must name their target.
+impl<AccountId> DeriveBeneficiary<AccountId> for () {
+	type Location = ();
+
+	fn derivative_account(_: &AccountId, _: u16) -> Option<AccountId> {
+		None
+	}
+
+	fn sovereign_account(_: &()) -> Option<AccountId> {
+		None
+	}
+}
+
+/// A signature which never verifies, for runtimes without `vest_unsigned`. Use it as both the
+/// `OffchainSignature` and the `OffchainPublic`.
+///
+/// Terms required with `require_terms` can then never be accepted with `accept_terms`, which
+/// verifies the same signature type, so they must be lifted with `clear_terms` instead.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct NoSignature<AccountId>(PhantomData<AccountId>);
+
+impl<AccountId: Default> IdentifyAccount for NoSignature<AccountId> {
+	type AccountId = AccountId;
+
+	fn into_account(self) -> AccountId {
+		Default::default()
+	}
+}
+
+impl<AccountId: Default> Verify for NoSignature<AccountId> {
+	type Signer = Self;
+
+	fn verify<L: sp_runtime::traits::Lazy<[u8]>>(&self, _: L, _: &AccountId) -> bool {
+		false
+	}
+}
+
+/// NFTs which can't be minted, for runtimes without vesting positions. Use with `()` as the
+/// `PositionClass`.
+pub struct NoPositions;
+
+impl<AccountId> nonfungibles::Inspect<AccountId> for NoPositions {
+	type InstanceId = u32;
+	type ClassId = ();
+
+	fn owner(_: &(), _: &u32) -> Option<AccountId> {
+		None
+	}
+}
+
+impl<AccountId> nonfungibles::Mutate<AccountId> for NoPositions {}
+
+/// Read access to the vesting schedules of accounts, for use by other pallets.
+pub trait VestingInspect<AccountId> {
+	/// The balance type of the vested currency.
+	type Balance;
+	/// The block number type used by vesting schedules.
+	type BlockNumber;
+
+	/// The vesting schedule of `who`, or `None` if the account is not vesting.
+	fn vesting_schedule(who: &AccountId) -> Option<VestingInfo<Self::Balance, Self::BlockNumber>>;
+
+	/// The amount of `who`'s balance which will still be locked by vesting at block `at`, or `None`
+	/// if the account is not vesting.
+	fn vesting_balance_at(who: &AccountId, at: Self::BlockNumber) -> Option<Self::Balance>;
+
+	/// The amount of `who`'s unvested balance which may be bonded for staking.
+	fn stakeable_unvested(who: &AccountId) -> Self::Balance;
+}
+
+/// A summary of an account's vesting as of the current block.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingSummary<Balance, BlockNumber> {
+	/// The account's vesting schedule.
+	pub schedule: VestingInfo<Balance, BlockNumber>,
+	/// Amount of the account's free balance which is still unvested.
+	pub unvested: Balance,
+	/// Amount which has vested but is still locked until the account next vests.
+	pub vestable: Balance,
+}
+
+/// The state of a vesting schedule as of the current block.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ScheduleStatus<Balance, BlockNumber> {
+	/// The vesting schedule.
+	pub schedule: VestingInfo<Balance, BlockNumber>,
+	/// The block at which the schedule is fully vested at the current unlock multiplier, if ever.
+	pub ending_block: Option<BlockNumber>,
+	/// Amount of the schedule which has vested so far.
+	pub vested: Balance,
+	/// Amount of the schedule which is still to vest.
+	pub remaining: Balance,
+	/// The vesting plan the schedule was derived from, if any.
+	pub plan: Option<u32>,
+	/// Whether governance may revoke the schedule, by way of its plan.
+	pub revocable: bool,
+	/// The block at which the schedule was created, unless it predates this being recorded.
+	pub created_at: Option<BlockNumber>,
+}
+
+/// Running totals of everything an account has ever vested.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct VestingTotals<Balance> {
+	/// Number of vesting schedules the account has received.
+	pub schedules: u32,
+	/// Total amount the account has received under vesting schedules.
+	pub received: Balance,
+	/// Total amount which has been unlocked through vesting.
+	pub vested: Balance,
+	/// Total amount which was still locked when a schedule was removed before completing.
+	pub revoked: Balance,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> VestingTotals<Balance> {
+	/// Amount which has been received but is neither vested nor revoked, i.e. is still locked.
+	pub fn locked(&self) -> Balance {
+		self.received.saturating_sub(self.vested).saturating_sub(self.revoked)
+	}
+
+	/// Account for a new vesting schedule locking `locked`.
+	fn receive(&mut self, locked: Balance) {
+		self.schedules = self.schedules.saturating_add(1);
+		self.received = self.received.saturating_add(locked);
+	}
+
+	/// Account for the amount locked having been reduced to `locked_now`, returning the amount which
+	/// was newly unlocked.
+	fn vest(&mut self, locked_now: Balance) -> Balance {
+		// Lowering the unlock multiplier may lock funds again.
+		let relocked = locked_now.saturating_sub(self.locked());
+		self.vested = self.vested.saturating_sub(relocked);
+		let unlocked = self.locked().saturating_sub(locked_now);
+		self.vested = self.vested.saturating_add(unlocked);
+		unlocked
+	}
+
+	/// Account for a schedule with `locked_now` still locked having been removed.
+	fn revoke(&mut self, locked_now: Balance) {
+		self.vest(locked_now);
+		self.revoked = self.revoked.saturating_add(locked_now);
+	}
+}
+
+// A value placed in storage that represents the current version of the Vesting storage. This value
+// is used by the `on_runtime_upgrade` logic to determine whether we run storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum Releases {
+	V0,
+	V1, // lifetime totals for every vesting account.
+	V2, // no schedules with a zero `locked` or `per_block`.
+	V3, // schedules indexed by the block they end.
+	V4, // schedules stored as `VersionedVestingInfo`.
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V0
+	}
+}
+
+/// Matches the calls of this pallet which only unlock vested funds, i.e. `vest` and `vest_other`.
+///
+/// Suitable for implementing a "Vesting" proxy type's `InstanceFilter`, so that the calls it
+/// allows are kept in line with this pallet.
+pub struct VestingProxy<T>(PhantomData<T>);
+impl<T: Config, C: IsSubType<Call<T>>> Filter<C> for VestingProxy<T> {
+	fn filter(c: &C) -> bool {
+		matches!(c.is_sub_type(), Some(Call::vest(..)) | Some(Call::vest_other(..)))
 	}
 }
 
@@ -115,245 +525,3741 @@ pub mod pallet {
 	use super::*;
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 		/// The overarching event type.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
-		/// The currency trait.
-		type Currency: LockableCurrency<Self::AccountId>;
+		/// The currency trait.
+		type Currency: LockableCurrency<Self::AccountId> + NamedReservableCurrency<Self::AccountId>;
+
+		/// The identifier of the named reserve holding the funds of streams and vesting positions
+		/// and the deposits of vested transfers, so that they are kept apart from whatever other
+		/// pallets reserve from the sender.
+		type ReserveId: Get<ReserveIdentifierOf<Self>>;
+
+		/// The minimum amount transferred to call `vested_transfer`, until set otherwise with
+		/// `set_min_vested_transfer`.
+		#[pallet::constant]
+		type MinVestedTransfer: Get<BalanceOf<Self>>;
+
+		/// The maximum amount which may be locked under an account's vesting schedule, if any.
+		#[pallet::constant]
+		type MaxTotalVested: Get<Option<BalanceOf<Self>>>;
+
+		/// How `MinVestedTransfer` is enforced.
+		#[pallet::constant]
+		type MinVestedTransferPolicy: Get<MinVestedTransferPolicy>;
+
+		/// The maximum number of vested transfers an account may receive per
+		/// `IncomingSchedulePeriod`.
+		#[pallet::constant]
+		type MaxIncomingSchedules: Get<u32>;
+
+		/// The length of the periods over which incoming vested transfers are limited, in blocks.
+		/// Zero disables the limit.
+		#[pallet::constant]
+		type IncomingSchedulePeriod: Get<Self::BlockNumber>;
+
+		/// The deposit reserved from the creator of a vested transfer until the schedule it
+		/// creates has ended.
+		#[pallet::constant]
+		type ScheduleDeposit: Get<BalanceOf<Self>>;
+
+		/// Whether completed vesting schedules should be kept in `CompletedVesting` rather than
+		/// simply being deleted.
+		#[pallet::constant]
+		type ArchiveCompletedSchedules: Get<bool>;
+
+		/// The maximum number of completed schedules kept in `CompletedVesting` for each account.
+		/// Once it is reached, the oldest one is deleted to make room for the next.
+		#[pallet::constant]
+		type MaxArchived: Get<u32>;
+
+		/// The overarching call type, used to schedule delayed vested transfers.
+		type ScheduledCall: From<Call<Self>>;
+
+		/// The scheduler used to dispatch delayed vested transfers and standing orders. With `()`,
+		/// neither can be created.
+		type Scheduler: ScheduleAnon<Self::BlockNumber, Self::ScheduledCall, Self::PalletsOrigin>;
+
+		/// Overarching type of all pallets origins.
+		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+
+		/// Signature with which an account authorises `vest_unsigned` on its behalf. With
+		/// `NoSignature`, `vest_unsigned` can't be used.
+		type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+		/// The public key which `OffchainSignature` is verified against.
+		type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// The priority of `vest_unsigned` transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// The maximum number of blocks for which a signature authorising `vest_unsigned` may be
+		/// valid.
+		#[pallet::constant]
+		type MaxUnsignedValidity: Get<Self::BlockNumber>;
+
+		/// The maximum number of matured schedules the offchain worker completes with
+		/// `vest_matured` each block. Zero disables the offchain worker.
+		#[pallet::constant]
+		type AutoVestLimit: Get<u32>;
+
+		/// The maximum number of accounts registered with `register_auto_vest` which are vested
+		/// in `on_initialize` at any one block.
+		#[pallet::constant]
+		type MaxAutoVestsPerBlock: Get<u32>;
+
+		/// The balance calls need, for `AutoVest` to only unlock vested funds when the signer
+		/// couldn't otherwise afford their call. `()` never unlocks anything.
+		type BalanceNeeded: BalanceNeeded<
+			Self::AccountId,
+			<Self as frame_system::Config>::Call,
+			BalanceOf<Self>,
+		>;
+
+		/// Accounts which may unlock their vested funds with `vest`, e.g. those with a sufficient
+		/// identity judgement.
+		type VestPrecondition: Contains<Self::AccountId>;
+
+		/// The external condition which must be met before vested funds may be unlocked.
+		type UnlockOracle: UnlockOracle<Self::AccountId, BalanceOf<Self>, Self::BlockNumber>;
+
+		/// Handler for when an account has become fully vested.
+		type OnVestingCompleted: OnVestingCompleted<Self::AccountId>;
+
+		/// Handler for funds being unlocked by `vest` or `vest_other`.
+		type OnVestUnlocked: OnVestUnlocked<Self::AccountId, BalanceOf<Self>>;
+
+		/// Handler for changes of the amount locked under an account's schedule, e.g.
+		/// `ShadowAsset`.
+		type OnLockedChanged: OnLockedChanged<Self::AccountId, BalanceOf<Self>>;
+
+		/// The bonus accrued every block by funds which are still locked, paid out when vesting.
+		/// Zero disables the bonus.
+		#[pallet::constant]
+		type BonusRate: Get<Perbill>;
+
+		/// Pays out accrued bonuses, e.g. from a pot or by minting. `()` pays nothing, for runtimes
+		/// without a bonus.
+		type BonusPayout: Pay<Self::AccountId, BalanceOf<Self>>;
+
+		/// The NFTs representing vesting positions, whose owners are the positions' beneficiaries.
+		/// With `NoPositions`, no position can be created.
+		type Positions: nonfungibles::Mutate<Self::AccountId, InstanceId = u32>;
+
+		/// The class of `Positions` in which vesting positions are minted. It must exist, and is
+		/// best created for this purpose alone.
+		type PositionClass: Get<PositionClassOf<Self>>;
+
+		/// The number of blocks for which a schedule must have fully matured without its account
+		/// vesting before the account's contingent beneficiary may claim the funds.
+		#[pallet::constant]
+		type ContingencyPeriod: Get<Self::BlockNumber>;
+
+		/// Whether `vest_other` may only be called by the target itself, one of the `VestOperators`
+		/// or a vester approved by the target, rather than by anyone.
+		#[pallet::constant]
+		type PermissionedVestOther: Get<bool>;
+
+		/// Whether `vest` and `vest_other` on an account which is not vesting are a free no-op, so
+		/// that they can't fail a batch, rather than failing with `NotVesting`.
+		///
+		/// As anyone can then make such calls free of charge, only set this where transactions are
+		/// otherwise rate-limited.
+		#[pallet::constant]
+		type NoOpVestWhenNotVesting: Get<bool>;
+
+		/// The operators which may always call `vest_other`.
+		type VestOperators: Contains<Self::AccountId>;
+
+		/// The origin which may make vested transfers, placing vesting schedules on other accounts.
+		type VestedTransferOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+
+		/// The origin which may make forced vested transfers and manage terms and vesting plans.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may set the unlock multiplier.
+		type UnlockMultiplierOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The greatest unlock multiplier which may be set.
+		#[pallet::constant]
+		type MaxUnlockMultiplier: Get<FixedU128>;
+
+		/// The number of blocks for which a schedule must have fully matured without its account
+		/// vesting before anyone may sweep it, or `None` if schedules may not be swept.
+		#[pallet::constant]
+		type SweepDelay: Get<Option<Self::BlockNumber>>;
+
+		/// The account receiving the funds of swept schedules, e.g. the treasury. With `None` the
+		/// funds are only unlocked and remain with their account.
+		#[pallet::constant]
+		type SweepDestination: Get<Option<Self::AccountId>>;
+
+		/// The fraction of the unvested funds forfeited when leaving a self-imposed schedule with
+		/// `exit_early`.
+		#[pallet::constant]
+		type EarlyExitPenalty: Get<Perbill>;
+
+		/// Handler for the penalties of early exits, e.g. the treasury. They are burned with `()`.
+		type EarlyExitPenaltyHandler: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Pays out the allocations claimed with `claim_vesting`, e.g. from a pot or by minting.
+		/// With `()`, nothing can be claimed.
+		type ClaimsPayout: Pay<Self::AccountId, BalanceOf<Self>>;
+
+		/// Derives the targets of `vested_transfer_to_derivative` and
+		/// `vested_transfer_to_sovereign`. With `()`, neither can be used.
+		type Beneficiaries: DeriveBeneficiary<Self::AccountId>;
+
+		/// The maximum length of the Merkle proofs accepted by `claim_vesting`.
+		#[pallet::constant]
+		type MaxClaimProofLength: Get<u32>;
+
+		/// The maximum length of the metadata tagging a schedule, e.g. "seed" or "team".
+		#[pallet::constant]
+		type MaxMetadataLen: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Information regarding the vesting of a given account.
+	///
+	/// The starting block of each schedule is kept as a reading of the vesting clock (see
+	/// `VestingClock`). Read through `Pallet::vesting`, which converts the schedule to the latest
+	/// layout.
+	#[pallet::storage]
+	pub type Vesting<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		VersionedVestingInfo<BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	/// The latest `MaxArchived` vesting schedules which have completed, oldest first, along with
+	/// the block at which they did so.
+	///
+	/// Only populated when `ArchiveCompletedSchedules` is enabled.
+	#[pallet::storage]
+	#[pallet::getter(fn completed_vesting)]
+	pub type CompletedVesting<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<(VestingInfo<BalanceOf<T>, T::BlockNumber>, T::BlockNumber), T::MaxArchived>,
+		ValueQuery,
+	>;
+
+	/// Lifetime vesting totals of each account which has ever received a vesting schedule.
+	///
+	/// Accounts which were already vesting before these totals were introduced have them
+	/// initialised from their schedule by the `V1` migration.
+	#[pallet::storage]
+	#[pallet::getter(fn lifetime_totals)]
+	pub type LifetimeTotals<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		VestingTotals<BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	/// The hash of the statement each account must sign with `accept_terms` before it may unlock
+	/// any vested funds.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_terms)]
+	pub type PendingTerms<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::Hash>;
+
+	/// The accounts each account has approved to call `vest_other` on its behalf.
+	#[pallet::storage]
+	pub type ApprovedVesters<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The standing orders of each funder, by order index.
+	#[pallet::storage]
+	#[pallet::getter(fn standing_orders)]
+	pub type StandingOrders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		u32,
+		StandingOrderOf<T>,
+	>;
+
+	/// The index of the next standing order to be created.
+	#[pallet::storage]
+	#[pallet::getter(fn standing_order_count)]
+	pub type StandingOrderCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The vested transfers each account has scheduled, by transfer index.
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_transfers)]
+	pub type ScheduledTransfers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		u32,
+		ScheduledTransferOf<T>,
+	>;
+
+	/// The index of the next vested transfer to be scheduled.
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_transfer_count)]
+	pub type ScheduledTransferCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Streams of funds, by stream index.
+	#[pallet::storage]
+	#[pallet::getter(fn streams)]
+	pub type Streams<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u32,
+		Stream<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	/// The index of the next stream to be created.
+	#[pallet::storage]
+	#[pallet::getter(fn stream_count)]
+	pub type StreamCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Vesting plans, by plan index.
+	#[pallet::storage]
+	#[pallet::getter(fn vesting_plans)]
+	pub type VestingPlans<T: Config> =
+		StorageMap<_, Twox64Concat, u32, VestingPlan<T::BlockNumber>>;
+
+	/// The index of the next vesting plan to be created.
+	#[pallet::storage]
+	#[pallet::getter(fn vesting_plan_count)]
+	pub type VestingPlanCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The plan from which each account's vesting schedule was derived, if any.
+	#[pallet::storage]
+	#[pallet::getter(fn plan_of)]
+	pub type PlanOf<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32>;
+
+	/// The accounts with a vesting schedule derived from each plan.
+	#[pallet::storage]
+	pub type PlanMembers<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u32, Blake2_128Concat, T::AccountId, ()>;
+
+	/// Vesting positions, by the index of the NFT representing them.
+	#[pallet::storage]
+	#[pallet::getter(fn positions)]
+	pub type Positions<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u32,
+		Position<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	/// The index of the next vesting position to be created.
+	#[pallet::storage]
+	#[pallet::getter(fn position_count)]
+	pub type PositionCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The metadata labelling each vesting account's schedule, set when it was created with
+	/// `vested_transfer_with_metadata`.
+	#[pallet::storage]
+	#[pallet::getter(fn schedule_metadata)]
+	pub type ScheduleMetadata<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u8, T::MaxMetadataLen>>;
+
+	/// The account to which each vesting account routes a fraction of the funds it unlocks, e.g.
+	/// for tax withholding, and that fraction.
+	#[pallet::storage]
+	#[pallet::getter(fn withholding)]
+	pub type Withholding<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::AccountId, Perbill)>;
+
+	/// The account designated by each vesting account to claim its funds should it stop vesting
+	/// them, e.g. due to lost keys.
+	#[pallet::storage]
+	#[pallet::getter(fn contingent_beneficiary)]
+	pub type ContingentBeneficiary<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId>;
+
+	#[pallet::type_value]
+	pub fn UnlockMultiplierOnEmpty() -> FixedU128 { FixedU128::one() }
+
+	/// The factor by which the rate of unlocking of all vesting schedules is scaled.
+	#[pallet::storage]
+	#[pallet::getter(fn unlock_multiplier)]
+	pub type UnlockMultiplier<T: Config> =
+		StorageValue<_, FixedU128, ValueQuery, UnlockMultiplierOnEmpty>;
+
+	/// The block at which the vesting clock last changed rate, and its reading then.
+	///
+	/// Vesting schedules progress with the vesting clock rather than the block number. It runs at
+	/// the unlock multiplier, and stands still while progression is frozen, so that changes to
+	/// either only apply from the block at which they are made. Until then it reads the block
+	/// number.
+	#[pallet::storage]
+	#[pallet::getter(fn vesting_clock)]
+	pub type VestingClock<T: Config> = StorageValue<_, (T::BlockNumber, FixedU128), ValueQuery>;
+
+	#[pallet::type_value]
+	pub fn MinVestedTransferOnEmpty<T: Config>() -> BalanceOf<T> { T::MinVestedTransfer::get() }
+
+	/// The minimum amount transferred to call `vested_transfer`.
+	#[pallet::storage]
+	#[pallet::getter(fn min_vested_transfer)]
+	pub type MinimumVestedTransfer<T: Config> =
+		StorageValue<_, BalanceOf<T>, ValueQuery, MinVestedTransferOnEmpty<T>>;
+
+	/// Whether the creation of vesting schedules is paused.
+	#[pallet::storage]
+	#[pallet::getter(fn paused)]
+	pub type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The block at which the progression of vesting was frozen, while it is.
+	#[pallet::storage]
+	#[pallet::getter(fn progression_frozen_since)]
+	pub type ProgressionFrozenSince<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	/// The number of blocks for which the progression of vesting has been frozen in all, not
+	/// counting an ongoing freeze.
+	#[pallet::storage]
+	#[pallet::getter(fn total_frozen)]
+	pub type TotalFrozen<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// The Merkle root of the `(account, schedule)` allocations which may be claimed with
+	/// `claim_vesting`.
+	#[pallet::storage]
+	#[pallet::getter(fn claims_root)]
+	pub type ClaimsRoot<T: Config> = StorageValue<_, T::Hash>;
+
+	/// The accounts which have claimed their allocation under each claims root.
+	#[pallet::storage]
+	pub type Claimed<T: Config> =
+		StorageDoubleMap<_, Identity, T::Hash, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The account which reserved a deposit for each vesting schedule created by a vested
+	/// transfer, along with the amount.
+	#[pallet::storage]
+	#[pallet::getter(fn schedule_deposit)]
+	pub type ScheduleDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::AccountId, BalanceOf<T>)>;
+
+	/// The accounts whose vesting schedule is fully vested at each reading of the vesting clock.
+	#[pallet::storage]
+	pub type SchedulesEndingAt<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The account which funded each vesting schedule created by a vested transfer.
+	#[pallet::storage]
+	#[pallet::getter(fn funded_by)]
+	pub type FundedBy<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId>;
+
+	/// The accounts with a vesting schedule funded by each account, by vested transfer.
+	#[pallet::storage]
+	pub type FundedSchedules<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The accounts whose vesting schedule was placed on their own funds with `vest_own_balance`.
+	#[pallet::storage]
+	pub type SelfImposed<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The accounts registered with `register_auto_vest` to be vested at each block.
+	#[pallet::storage]
+	pub type AutoVestQueue<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::AccountId, T::MaxAutoVestsPerBlock>,
+		ValueQuery,
+	>;
+
+	/// The block at which each account registered with `register_auto_vest` will be vested.
+	#[pallet::storage]
+	#[pallet::getter(fn auto_vest_at)]
+	pub type AutoVestAt<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+	/// The start of the current period of each account receiving vested transfers, along with the
+	/// number of transfers received in it.
+	#[pallet::storage]
+	#[pallet::getter(fn incoming_schedules)]
+	pub type IncomingSchedules<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, u32)>;
+
+	/// The nonce which the next signature authorising `vest_unsigned` for an account must cover,
+	/// so that each signature is only used once.
+	#[pallet::storage]
+	#[pallet::getter(fn unsigned_vest_nonce)]
+	pub type UnsignedVestNonce<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The block up to which the bonus on each vesting account's locked funds has been paid.
+	#[pallet::storage]
+	#[pallet::getter(fn bonus_paid_until)]
+	pub type BonusPaidUntil<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+	/// Storage version of the pallet.
+	///
+	/// This is set to the latest version for new networks.
+	#[pallet::storage]
+	pub(crate) type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::extra_constants]
+	impl<T: Config> Pallet<T> {
+		//TODO: rename to snake case after https://github.com/paritytech/substrate/issues/8826 fixed.
+		/// The identifier of the lock placed on vesting funds.
+		#[allow(non_snake_case)]
+		fn VestingId() -> LockIdentifier {
+			VESTING_ID
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Vest the accounts registered with `register_auto_vest` for block `now`.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let queue = AutoVestQueue::<T>::take(now);
+			for who in queue.iter() {
+				AutoVestAt::<T>::remove(who);
+				if let Err(e) = Self::do_vest(who.clone()) {
+					log::debug!(
+						target: "runtime::vesting",
+						"failed to auto-vest {:?}: {:?}",
+						who,
+						e,
+					);
+				}
+			}
+			T::DbWeight::get().reads_writes(1, 1).saturating_add(
+				T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get())
+					.saturating_add(T::DbWeight::get().writes(1))
+					.saturating_mul(queue.len() as Weight),
+			)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if StorageVersion::<T>::get() == Releases::V0 {
+				weight = weight.saturating_add(migrations::v1::migrate::<T>());
+			}
+			if StorageVersion::<T>::get() == Releases::V1 {
+				weight = weight.saturating_add(migrations::v2::migrate::<T>());
+			}
+			if StorageVersion::<T>::get() == Releases::V2 {
+				weight = weight.saturating_add(migrations::v3::migrate::<T>());
+			}
+			if StorageVersion::<T>::get() == Releases::V3 {
+				weight = weight.saturating_add(migrations::v4::migrate::<T>());
+			}
+			weight
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			match StorageVersion::<T>::get() {
+				Releases::V0 => migrations::v1::pre_migrate::<T>(),
+				Releases::V1 => migrations::v2::pre_migrate::<T>(),
+				Releases::V2 => migrations::v3::pre_migrate::<T>(),
+				Releases::V3 => migrations::v4::pre_migrate::<T>(),
+				Releases::V4 => Ok(()),
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			migrations::v1::post_migrate::<T>()?;
+			migrations::v2::post_migrate::<T>()?;
+			migrations::v3::post_migrate::<T>()?;
+			migrations::v4::post_migrate::<T>()
+		}
+
+		/// Submit `vest_matured` for up to `AutoVestLimit` of the schedules which are fully vested
+		/// as of `now`.
+		///
+		/// Schedules which matured while no offchain worker ran are not revisited; their owners
+		/// can still complete them with `vest`.
+		fn offchain_worker(now: T::BlockNumber) {
+			let limit = T::AutoVestLimit::get() as usize;
+			if limit == 0 {
+				return
+			}
+			let ending = Self::clock_at(now);
+			for (who, ()) in SchedulesEndingAt::<T>::iter_prefix(ending).take(limit) {
+				let call = Call::vest_matured(who.clone()).into();
+				if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call).is_err() {
+					log::debug!(
+						target: "runtime::vesting",
+						"failed to submit vest_matured for {:?}",
+						who,
+					);
+				}
+			}
+		}
+	}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub vesting: Vec<GenesisVesting<T::AccountId, T::BlockNumber, BalanceOf<T>>>,
+		/// Explicit vesting schedules, locking funds the accounts have already been endowed with.
+		/// An account may only have one schedule.
+		pub schedules: Vec<(T::AccountId, VestingInfo<BalanceOf<T>, T::BlockNumber>)>,
+		/// Vesting schedules whose locked funds are minted into the account at genesis, so the
+		/// balances genesis need not be kept in sync with them.
+		pub endowed_schedules: Vec<(T::AccountId, VestingInfo<BalanceOf<T>, T::BlockNumber>)>,
+		/// As `vesting`, but giving the fraction of the account's endowment which is locked rather
+		/// than the amount which is liquid.
+		pub fractional_vesting: Vec<FractionalGenesisVesting<T::AccountId, T::BlockNumber>>,
+		/// As `vesting`, but giving the block by which the account is fully vested rather than
+		/// the length of the schedule.
+		pub vesting_until: Vec<GenesisVestingUntil<T::AccountId, T::BlockNumber, BalanceOf<T>>>,
+		/// Skip invalid entries, logging them, rather than failing the build. Either way every
+		/// invalid entry is reported.
+		pub skip_invalid: bool,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			GenesisConfig {
+				vesting: Default::default(),
+				schedules: Default::default(),
+				endowed_schedules: Default::default(),
+				fractional_vesting: Default::default(),
+				vesting_until: Default::default(),
+				skip_invalid: false,
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			use sp_runtime::traits::Saturating;
+
+			StorageVersion::<T>::put(Releases::V4);
+
+			let mut invalid = Vec::new();
+
+			// Generate initial vesting configuration. Entries of `fractional_vesting` leave liquid
+			// whatever their fraction doesn't lock, and those of `vesting_until` round `per_block`
+			// up so as to be fully vested by their `end`.
+			let fractional = self.fractional_vesting.iter().map(|entry| {
+				let balance = T::Currency::free_balance(&entry.who);
+				let liquid = balance.saturating_sub(entry.locked * balance);
+				(&entry.who, entry.begin, entry.length, liquid, false)
+			});
+			let until = self.vesting_until.iter().map(|entry| {
+				let length = entry.end.saturating_sub(entry.begin);
+				(&entry.who, entry.begin, length, entry.liquid, true)
+			});
+			for (who, begin, length, liquid, until) in self.vesting.iter()
+				.map(|entry| (&entry.who, entry.begin, entry.length, entry.liquid, false))
+				.chain(fractional)
+				.chain(until)
+			{
+				let balance = T::Currency::free_balance(who);
+				if balance.is_zero() {
+					invalid.push((who.clone(), "Currencies must be init'd before vesting"));
+					continue
+				}
+				if until && length.is_zero() {
+					invalid.push((who.clone(), "Vesting must end after it begins"));
+					continue
+				}
+				// Total genesis `balance` minus `liquid` equals funds locked for vesting
+				let locked = balance.saturating_sub(liquid);
+				let length_as_balance = block_number_to_balance::<_, BalanceOf<T>>(length).max(One::one());
+				let mut per_block = locked / length_as_balance;
+				if until && !(locked % length_as_balance).is_zero() {
+					per_block = per_block.saturating_add(One::one());
+				}
+				let per_block = per_block.max(One::one());
+
+				let schedule = VestingInfo {
+					locked: locked,
+					per_block: per_block,
+					starting_block: begin
+				};
+				if let Err(reason) = Pallet::<T>::insert_genesis_schedule(who, schedule, false) {
+					invalid.push((who.clone(), reason));
+				}
+			}
+
+			let schedules = self.schedules.iter().map(|e| (e, false))
+				.chain(self.endowed_schedules.iter().map(|e| (e, true)));
+			for ((who, schedule), endow) in schedules {
+				if schedule.locked.is_zero() || schedule.per_block.is_zero() {
+					invalid.push((
+						who.clone(),
+						"Vesting schedules must lock funds and vest some every block",
+					));
+					continue
+				}
+				if let Err(reason) = Pallet::<T>::insert_genesis_schedule(who, *schedule, endow) {
+					invalid.push((who.clone(), reason));
+				}
+			}
+
+			if invalid.is_empty() {
+				return
+			}
+			if !self.skip_invalid {
+				panic!("Invalid vesting genesis entries: {:?}", invalid);
+			}
+			for (who, reason) in invalid {
+				log::warn!(
+					target: "runtime::vesting",
+					"skipping genesis vesting of {:?}: {}",
+					who,
+					reason,
+				);
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		BalanceOf<T> = "Balance",
+		T::BlockNumber = "BlockNumber",
+		T::Hash = "Hash",
+	)]
+	pub enum Event<T: Config> {
+		/// The amount vested has been updated. This could indicate more funds are available. The
+		/// balance given is the amount which is left unvested (and thus locked).
+		VestingUpdated { account: T::AccountId, unvested: BalanceOf<T> },
+		/// An account has become fully vested. No further vesting can happen.
+		VestingCompleted { account: T::AccountId },
+		/// A vested transfer has been scheduled to happen at a later block.
+		VestedTransferScheduled {
+			source: T::AccountId,
+			index: u32,
+			target: T::AccountId,
+			when: T::BlockNumber,
+		},
+		/// A scheduled vested transfer has been cancelled and its funds unreserved.
+		ScheduledTransferCancelled { source: T::AccountId, index: u32 },
+		/// A scheduled vested transfer has been attempted. Its funds are unreserved either way.
+		ScheduledTransferExecuted { source: T::AccountId, index: u32, result: DispatchResult },
+		/// An account must accept the terms of a statement before unlocking any vested funds.
+		TermsRequired { account: T::AccountId, statement_hash: T::Hash },
+		/// An account has accepted the terms it was required to.
+		TermsAccepted { account: T::AccountId },
+		/// The terms an account was required to accept have been lifted.
+		TermsCleared { account: T::AccountId },
+		/// A standing order has been created.
+		StandingOrderCreated { funder: T::AccountId, index: u32, target: T::AccountId },
+		/// A standing order has been modified.
+		StandingOrderModified { funder: T::AccountId, index: u32 },
+		/// A standing order has been cancelled.
+		StandingOrderCancelled { funder: T::AccountId, index: u32 },
+		/// A standing order has attempted a vested transfer.
+		StandingOrderExecuted { funder: T::AccountId, index: u32, result: DispatchResult },
+		/// A stream has been created.
+		StreamCreated { index: u32, sender: T::AccountId, recipient: T::AccountId },
+		/// The recipient of a stream has withdrawn from it.
+		StreamWithdrawn { index: u32, amount: BalanceOf<T> },
+		/// A stream has been cancelled, paying out what had been streamed and returning the rest
+		/// to the sender.
+		StreamCancelled { index: u32, paid_out: BalanceOf<T>, refunded: BalanceOf<T> },
+		/// A bonus has been paid on an account's locked funds.
+		BonusPaid { account: T::AccountId, bonus: BalanceOf<T> },
+		/// A vesting position has been created.
+		PositionCreated { index: u32, funder: T::AccountId, beneficiary: T::AccountId },
+		/// Vested funds of a position have been paid out.
+		PositionVested { index: u32, owner: T::AccountId, amount: BalanceOf<T> },
+		/// A vesting plan has been created.
+		VestingPlanCreated { index: u32 },
+		/// An account's vesting schedule has been revoked, `amount` of its unvested funds being
+		/// transferred away. This is less than the unvested funds if some were slashed.
+		VestingRevoked { account: T::AccountId, amount: BalanceOf<T> },
+		/// An account's vesting schedule could not be revoked as its unvested funds could not be
+		/// transferred away. It has been left in place.
+		VestingRevocationFailed { account: T::AccountId },
+		/// All schedules derived from a plan have been revoked.
+		VestingPlanRevoked { plan: u32 },
+		/// An account's contingent beneficiary has been set or cleared.
+		ContingentBeneficiarySet { account: T::AccountId, contingent: Option<T::AccountId> },
+		/// An account's schedule has been created labelled with `metadata`.
+		VestingMetadataSet { account: T::AccountId, metadata: Vec<u8> },
+		/// An account's withholding has been set or cleared.
+		WithholdingSet { account: T::AccountId, withholding: Option<(T::AccountId, Perbill)> },
+		/// Of the funds an account has unlocked, `withheld` have been routed to `destination` and
+		/// `kept` remain with the account.
+		VestingWithheld {
+			account: T::AccountId,
+			destination: T::AccountId,
+			withheld: BalanceOf<T>,
+			kept: BalanceOf<T>,
+		},
+		/// The funds of an account which stopped vesting have been claimed by its contingent
+		/// beneficiary.
+		ContingentClaimed { account: T::AccountId, contingent: T::AccountId, amount: BalanceOf<T> },
+		/// The schedule of an account which stopped vesting has been swept, moving the given
+		/// amount to the sweep destination.
+		VestingSwept { account: T::AccountId, amount: BalanceOf<T> },
+		/// The unlock multiplier has been set.
+		UnlockMultiplierSet { multiplier: FixedU128 },
+		/// An account has approved a vester.
+		VesterApproved { account: T::AccountId, vester: T::AccountId },
+		/// An account has revoked its approval of a vester.
+		VesterRevoked { account: T::AccountId, vester: T::AccountId },
+		/// The creation of vesting schedules has been paused.
+		SchedulesPaused,
+		/// The creation of vesting schedules has been unpaused.
+		SchedulesUnpaused,
+		/// The progression of vesting has been frozen as of block `at`.
+		ProgressionFrozen { at: T::BlockNumber },
+		/// The progression of vesting has been resumed, having been frozen for `frozen_for`
+		/// blocks.
+		ProgressionResumed { frozen_for: T::BlockNumber },
+		/// The minimum amount of vested transfers has been set.
+		MinVestedTransferSet { amount: BalanceOf<T> },
+		/// An account's schedule has been exported, leaving the given remaining schedule to be
+		/// imported elsewhere.
+		VestingExported {
+			account: T::AccountId,
+			remaining: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		},
+		/// A schedule has been imported onto an account.
+		VestingImported {
+			account: T::AccountId,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		},
+		/// A schedule has been imposed on funds an account already held.
+		VestingApplied {
+			account: T::AccountId,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		},
+		/// An account has placed its own funds under a schedule.
+		VestingSelfImposed {
+			account: T::AccountId,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		},
+		/// An account has left its self-imposed schedule early, forfeiting `penalty`.
+		VestingExitedEarly { account: T::AccountId, penalty: BalanceOf<T> },
+		/// The root of the allocations which may be claimed has been set or cleared.
+		ClaimsRootSet { root: Option<T::Hash> },
+		/// An account's allocation has been claimed and placed under its schedule.
+		VestingClaimed {
+			account: T::AccountId,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		},
+		/// An account has been registered to be vested automatically at block `at`.
+		AutoVestRegistered { account: T::AccountId, at: T::BlockNumber },
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::vest_unsigned(who, nonce, valid_until, signature) = call {
+				let now = <frame_system::Pallet<T>>::block_number();
+				if now > *valid_until {
+					return InvalidTransaction::Stale.into();
+				}
+				let expected = Self::unsigned_vest_nonce(who);
+				if *nonce < expected || !Self::can_vest(who) {
+					return InvalidTransaction::Stale.into();
+				}
+				if *nonce > expected {
+					return InvalidTransaction::Future.into();
+				}
+				// Signatures valid for longer than allowed are refused outright.
+				if valid_until.saturating_sub(now) > T::MaxUnsignedValidity::get() {
+					return InvalidTransaction::BadProof.into();
+				}
+
+				// check signature (this is expensive so we do it last).
+				if !signature.verify(&Self::vest_payload(who, *nonce, *valid_until)[..], who) {
+					return InvalidTransaction::BadProof.into();
+				}
+
+				ValidTransaction::with_tag_prefix("VestUnsigned")
+					.priority(T::UnsignedPriority::get())
+					.and_provides((who, nonce))
+					.longevity(
+						TryInto::<u64>::try_into(valid_until.saturating_sub(now))
+							.unwrap_or(64_u64)
+							.max(1),
+					)
+					.propagate(true)
+					.build()
+			} else if let Call::vest_matured(who) = call {
+				if !Self::is_matured(who) || !Self::can_vest(who) {
+					return InvalidTransaction::Stale.into();
+				}
+
+				ValidTransaction::with_tag_prefix("VestMatured")
+					.priority(T::UnsignedPriority::get())
+					.and_provides(who)
+					.longevity(64_u64)
+					.propagate(true)
+					.build()
+			} else {
+				InvalidTransaction::Call.into()
+			}
+		}
+	}
+
+	/// Error for the vesting pallet.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account given is not vesting.
+		NotVesting,
+		/// An existing vesting schedule already exists for this account that cannot be clobbered.
+		ExistingVestingSchedule,
+		/// Amount being transferred is too low to create a vesting schedule.
+		AmountLow,
+		/// Failed to create a new schedule because some parameter was invalid.
+		InvalidScheduleParams,
+		/// The account still holds funds, so its vesting schedule is not orphaned.
+		NotOrphaned,
+		/// The account does not yet meet the precondition for unlocking vested funds.
+		VestPreconditionUnmet,
+		/// The external condition for unlocking vested funds has not been met.
+		UnlockConditionUnmet,
+		/// The account must accept the terms of its statement before unlocking vested funds.
+		TermsNotAccepted,
+		/// The account has no terms pending acceptance.
+		NoTermsPending,
+		/// The signature is not a valid signature of the pending statement hash by the sender.
+		InvalidStatementSignature,
+		/// No standing order exists with the given index.
+		UnknownStandingOrder,
+		/// No scheduled vested transfer exists with the given index.
+		UnknownScheduledTransfer,
+		/// No stream exists with the given index.
+		UnknownStream,
+		/// The sender is not party to the stream in the required role.
+		NotStreamParty,
+		/// No vesting position exists with the given index.
+		UnknownPosition,
+		/// No vesting plan exists with the given index.
+		UnknownPlan,
+		/// The vesting plan is not revocable.
+		PlanNotRevocable,
+		/// The sender is not the account's contingent beneficiary.
+		NotContingentBeneficiary,
+		/// The account's schedule has not been fully matured for the contingency period.
+		ContingencyPeriodNotElapsed,
+		/// Schedules may not be swept.
+		SweepingDisabled,
+		/// The account's schedule has not been fully matured for the sweep delay.
+		NotSweepable,
+		/// The sender may not call `vest_other` for the target.
+		NotPermittedToVest,
+		/// The creation of vesting schedules is paused.
+		Paused,
+		/// The progression of vesting is already frozen.
+		ProgressionAlreadyFrozen,
+		/// The progression of vesting is not frozen.
+		ProgressionNotFrozen,
+		/// The unlock multiplier is zero or greater than `MaxUnlockMultiplier`.
+		InvalidUnlockMultiplier,
+		/// The target has received too many vested transfers in the current period.
+		TooManyIncomingSchedules,
+		/// The schedule would lock more than `MaxTotalVested`.
+		TooMuchVested,
+		/// The account's free balance is too low to cover the schedule placed on it.
+		BalanceTooLow,
+		/// The account's schedule is not fully vested yet.
+		NotMatured,
+		/// The account's schedule was not placed on its own funds by itself.
+		NotSelfImposed,
+		/// There are no allocations to claim.
+		NoClaimsRoot,
+		/// The proof does not show the allocation to be part of the claims root.
+		InvalidClaimProof,
+		/// The proof is longer than `MaxClaimProofLength`.
+		ClaimProofTooLong,
+		/// The account has already claimed its allocation.
+		AlreadyClaimed,
+		/// The account is already registered to be vested automatically.
+		AlreadyRegistered,
+		/// Too many accounts are registered to be vested automatically at the account's
+		/// ending block.
+		AutoVestQueueFull,
+		/// No account can be derived for the given beneficiary.
+		NoDerivedAccount,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Unlock any vested funds of the sender account.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// If the sender has no funds locked under this pallet this fails with `NotVesting`, or is a
+		/// no-op which does not pay a fee if `NoOpVestWhenNotVesting` is set, so that it can be
+		/// safely included in batches. Otherwise the sender must satisfy `VestPrecondition`. The
+		/// call is also free when it completes the schedule, as it then only cleans up the sender's
+		/// lock and storage.
+		///
+		/// The vesting lock doesn't cover transaction payment, so the fee can be paid even when
+		/// the sender's whole balance is still locked.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 20 Reads, 22 Writes when completing the schedule
+		///     - Reads: Vesting Storage, Pending Terms, Vesting Clock, Unlock Multiplier,
+		///       Progression Frozen Since, Bonus Paid Until, Withholding, Lifetime Totals,
+		///       Balances Locks, [Sender Account], Bonus Payout Account, Withholding Account,
+		///       Schedule Deposits, Balances Reserves, Depositor Account, Plan Of, Funded By,
+		///       Auto Vest At, Auto Vest Queue, Completed Vesting
+		///     - Writes: Vesting Storage, Bonus Paid Until, Withholding, Lifetime Totals,
+		///       Balances Locks, [Sender Account], Bonus Payout Account, Withholding Account,
+		///       Schedules Ending At, Contingent Beneficiary, Schedule Metadata, Schedule Deposits,
+		///       Balances Reserves, Depositor Account, Plan Of, Plan Members, Funded By,
+		///       Funded Schedules, Self Imposed, Auto Vest At, Auto Vest Queue, Completed Vesting
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vest_locked(MaxLocksOf::<T>::get())
+			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get()))
+		)]
+		pub fn vest(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::do_vest_free_on_completion(who)
+		}
+
+		/// Unlock any vested funds of a `target` account.
+		///
+		/// The dispatch origin for this call must be _Signed_. If `PermissionedVestOther` is set,
+		/// the sender must be `target` itself, one of the `VestOperators` or a vester approved by
+		/// `target`.
+		///
+		/// - `target`: The account whose vested funds should be unlocked. If it has no funds locked
+		/// under this pallet this fails with `NotVesting`, or is a no-op which does not pay a fee if
+		/// `NoOpVestWhenNotVesting` is set.
+		///
+		/// The call is free when it completes the target's schedule, so that matured schedules of
+		/// inactive accounts can be cleaned up by anyone at no cost.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 21 Reads, 22 Writes when completing the schedule
+		///     - Reads: Approved Vesters, Vesting Storage, Pending Terms, Vesting Clock,
+		///       Unlock Multiplier, Progression Frozen Since, Bonus Paid Until, Withholding,
+		///       Lifetime Totals, Balances Locks, Target Account, Bonus Payout Account,
+		///       Withholding Account, Schedule Deposits, Balances Reserves, Depositor Account,
+		///       Plan Of, Funded By, Auto Vest At, Auto Vest Queue, Completed Vesting
+		///     - Writes: Vesting Storage, Bonus Paid Until, Withholding, Lifetime Totals,
+		///       Balances Locks, Target Account, Bonus Payout Account, Withholding Account,
+		///       Schedules Ending At, Contingent Beneficiary, Schedule Metadata, Schedule Deposits,
+		///       Balances Reserves, Depositor Account, Plan Of, Plan Members, Funded By,
+		///       Funded Schedules, Self Imposed, Auto Vest At, Auto Vest Queue, Completed Vesting
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get())
+			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
+		)]
+		pub fn vest_other(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			ensure!(
+				!T::PermissionedVestOther::get() ||
+					who == target ||
+					T::VestOperators::contains(&who) ||
+					ApprovedVesters::<T>::contains_key(&target, &who),
+				Error::<T>::NotPermittedToVest,
+			);
+			Self::do_vest_free_on_completion(target)
+		}
+
+		/// Create a vested transfer. `ScheduleDeposit` is reserved from the sender until the
+		/// schedule has ended.
+		///
+		/// The dispatch origin for this call must be `VestedTransferOrigin`.
+		///
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `amount`: The amount of funds to transfer and will be vested.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		///
+		/// Emits `VestingCreated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 4 Writes
+		///     - Reads: Vesting Storage, Lifetime Totals, Balances Locks, Target Account, [Sender Account]
+		///     - Writes: Vesting Storage, Lifetime Totals, Balances Locks, Target Account, [Sender Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get()))]
+		pub fn vested_transfer(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			let transactor = T::VestedTransferOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(&transactor, &target, schedule, false)
+		}
+
+		/// Create a vested transfer to a derivative account of `owner`, as used by the utility
+		/// pallet's `as_derivative`.
+		///
+		/// The dispatch origin for this call must be `VestedTransferOrigin`.
+		///
+		/// - `owner`: The account controlling the derivative account.
+		/// - `index`: The index of the derivative account.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		///
+		/// Emits `VestingCreated`.
+		///
+		/// # <weight>
+		/// Same as `vested_transfer`.
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get()))]
+		pub fn vested_transfer_to_derivative(
+			origin: OriginFor<T>,
+			owner: <T::Lookup as StaticLookup>::Source,
+			index: u16,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			let transactor = T::VestedTransferOrigin::ensure_origin(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let target = Self::derivative_account_id(&owner, index)
+				.ok_or(Error::<T>::NoDerivedAccount)?;
+			Self::do_vested_transfer(&transactor, &target, schedule, false)
+		}
+
+		/// Create a vested transfer to the sovereign account of `location`, e.g. a parachain.
+		///
+		/// The dispatch origin for this call must be `VestedTransferOrigin`.
+		///
+		/// - `location`: The location owning the sovereign account.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		///
+		/// Emits `VestingCreated`.
+		///
+		/// # <weight>
+		/// Same as `vested_transfer`.
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get()))]
+		pub fn vested_transfer_to_sovereign(
+			origin: OriginFor<T>,
+			location: LocationOf<T>,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			let transactor = T::VestedTransferOrigin::ensure_origin(origin)?;
+			let target = Self::sovereign_account_id(&location)
+				.ok_or(Error::<T>::NoDerivedAccount)?;
+			Self::do_vested_transfer(&transactor, &target, schedule, false)
+		}
+
+		/// Force a vested transfer.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `source`: The account whose funds should be transferred.
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `amount`: The amount of funds to transfer and will be vested.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		///
+		/// Emits `VestingCreated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 5 Reads, 5 Writes
+		///     - Reads: Vesting Storage, Lifetime Totals, Balances Locks, Target Account, Source Account
+		///     - Writes: Vesting Storage, Lifetime Totals, Balances Locks, Target Account, Source Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get()))]
+		pub fn force_vested_transfer(
+			origin: OriginFor<T>,
+			source: <T::Lookup as StaticLookup>::Source,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			let source = T::Lookup::lookup(source)?;
+			Self::do_vested_transfer(&source, &target, schedule, true)
+		}
+
+		/// Schedule a vested transfer from the sender to happen at a later block.
+		///
+		/// The dispatch origin for this call must be `VestedTransferOrigin`. The transferred amount
+		/// is reserved from the sender until the transfer is made by `execute_scheduled_transfer`
+		/// at `when`, or until the sender calls `cancel_scheduled_transfer`. The transfer is
+		/// subject to the same checks as `vested_transfer` at `when`; if it fails then, the funds
+		/// are only unreserved.
+		///
+		/// - `when`: The block at which the vested transfer should happen.
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		///
+		/// Emits `VestedTransferScheduled`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 5 Writes
+		///     - Reads: Scheduled Transfer Count, Scheduler Agenda, Sender Reserves
+		///     - Writes: Scheduled Transfer Count, Scheduled Transfers, Scheduler Agenda, Sender
+		///       Reserves, Sender Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::schedule_vested_transfer())]
+		pub fn schedule_vested_transfer(
+			origin: OriginFor<T>,
+			when: T::BlockNumber,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			let transactor = T::VestedTransferOrigin::ensure_origin(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			Self::ensure_min_vested_transfer(&schedule, false)?;
+			ensure!(
+				!schedule.locked.is_zero() && !schedule.per_block.is_zero(),
+				Error::<T>::InvalidScheduleParams,
+			);
+			let target = T::Lookup::lookup(target)?;
+
+			let index = ScheduledTransferCount::<T>::get();
+			T::Currency::reserve_named(&T::ReserveId::get(), &transactor, schedule.locked)?;
+			let call = Call::<T>::execute_scheduled_transfer(transactor.clone(), index);
+			let task = T::Scheduler::schedule(
+				DispatchTime::At(when),
+				None,
+				LOWEST_PRIORITY,
+				frame_system::RawOrigin::Root.into(),
+				call.into(),
+			);
+			let task = match task {
+				Ok(task) => task,
+				Err(e) => {
+					T::Currency::unreserve_named(&T::ReserveId::get(), &transactor, schedule.locked);
+					return Err(e)
+				},
+			};
+			ScheduledTransferCount::<T>::put(index.saturating_add(1));
+			ScheduledTransfers::<T>::insert(&transactor, index, ScheduledTransfer {
+				target: target.clone(),
+				schedule,
+				task,
+			});
+
+			Self::deposit_event(
+				Event::<T>::VestedTransferScheduled { source: transactor, index, target, when },
+			);
+			Ok(())
+		}
+
+		/// Cancel one of the sender's scheduled vested transfers, unreserving its funds.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `index`: The index of the scheduled transfer.
+		///
+		/// Emits `ScheduledTransferCancelled`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 4 Writes
+		///     - Reads: Scheduled Transfers, Scheduler Agenda, Sender Reserves
+		///     - Writes: Scheduled Transfers, Scheduler Agenda, Sender Reserves, Sender Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::cancel_scheduled_transfer())]
+		pub fn cancel_scheduled_transfer(
+			origin: OriginFor<T>,
+			#[pallet::compact] index: u32,
+		) -> DispatchResult {
+			let source = ensure_signed(origin)?;
+			let transfer = ScheduledTransfers::<T>::get(&source, index)
+				.ok_or(Error::<T>::UnknownScheduledTransfer)?;
+			T::Scheduler::cancel(transfer.task)
+				.map_err(|_| Error::<T>::UnknownScheduledTransfer)?;
+			T::Currency::unreserve_named(&T::ReserveId::get(), &source, transfer.schedule.locked);
+			ScheduledTransfers::<T>::remove(&source, index);
+
+			Self::deposit_event(Event::<T>::ScheduledTransferCancelled { source, index });
+			Ok(())
+		}
+
+		/// Make a scheduled vested transfer.
+		///
+		/// The dispatch origin for this call must be _Root_. It is dispatched by the scheduler on
+		/// behalf of the source of the transfer.
+		///
+		/// Emits `ScheduledTransferExecuted`, along with the events of the transfer if it succeeds.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 7 Reads, 7 Writes
+		///     - Reads: Scheduled Transfers, Sender Reserves, Vesting Storage, Lifetime Totals,
+		///       Balances Locks, Sender Account, Target Account
+		///     - Writes: Scheduled Transfers, Sender Reserves, Vesting Storage, Lifetime Totals,
+		///       Balances Locks, Sender Account, Target Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::execute_scheduled_transfer(MaxLocksOf::<T>::get()))]
+		pub fn execute_scheduled_transfer(
+			origin: OriginFor<T>,
+			source: T::AccountId,
+			#[pallet::compact] index: u32,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let transfer = ScheduledTransfers::<T>::take(&source, index)
+				.ok_or(Error::<T>::UnknownScheduledTransfer)?;
+
+			T::Currency::unreserve_named(&T::ReserveId::get(), &source, transfer.schedule.locked);
+			let result = with_transaction(|| {
+				let result =
+					Self::do_vested_transfer(&source, &transfer.target, transfer.schedule, false);
+				match result {
+					Ok(()) => TransactionOutcome::Commit(result),
+					Err(_) => TransactionOutcome::Rollback(result),
+				}
+			});
+
+			Self::deposit_event(Event::<T>::ScheduledTransferExecuted { source, index, result });
+			Ok(())
+		}
+
+		/// Unlock any vested funds of `who` on their behalf.
+		///
+		/// The dispatch origin for this call must be _None_; `signature` must be `who`'s signature of
+		/// `vest_payload(who, nonce, valid_until)`. This allows anyone to relay the unlock without
+		/// paying a fee for it, and is only valid while `who` has vested funds to unlock. Each
+		/// signature may only be used once, and for at most `MaxUnsignedValidity` blocks.
+		///
+		/// - `who`: The account whose vested funds should be unlocked.
+		/// - `nonce`: The current `UnsignedVestNonce` of `who`.
+		/// - `valid_until`: The last block at which the signature may be used.
+		/// - `signature`: `who`'s signature authorising the unlock.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 21 Reads, 23 Writes when completing the schedule
+		///     - Reads: Unsigned Vest Nonce, Vesting Storage, Pending Terms, Vesting Clock,
+		///       Unlock Multiplier, Progression Frozen Since, Bonus Paid Until, Withholding,
+		///       Lifetime Totals, Balances Locks, Target Account, Bonus Payout Account,
+		///       Withholding Account, Schedule Deposits, Balances Reserves, Depositor Account,
+		///       Plan Of, Funded By, Auto Vest At, Auto Vest Queue, Completed Vesting
+		///     - Writes: Unsigned Vest Nonce, Vesting Storage, Bonus Paid Until, Withholding,
+		///       Lifetime Totals, Balances Locks, Target Account, Bonus Payout Account,
+		///       Withholding Account, Schedules Ending At, Contingent Beneficiary,
+		///       Schedule Metadata, Schedule Deposits, Balances Reserves, Depositor Account,
+		///       Plan Of, Plan Members, Funded By, Funded Schedules, Self Imposed, Auto Vest At,
+		///       Auto Vest Queue, Completed Vesting
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get())
+			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
+			.saturating_add(T::DbWeight::get().writes(1))
+		)]
+		pub fn vest_unsigned(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			nonce: u32,
+			// since signature verification is done in `validate_unsigned`
+			// we can skip doing it here again.
+			_valid_until: T::BlockNumber,
+			_signature: T::OffchainSignature,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			UnsignedVestNonce::<T>::insert(&who, nonce.saturating_add(1));
+			Self::do_vest(who)
+		}
+
+		/// Remove the vesting schedule of an account which no longer holds any funds.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: An account with a vesting schedule but no balance left, as can be left behind
+		/// by accounts reaped before vesting schedules were cleaned up on reaping.
+		///
+		/// The call is free if it succeeds.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 2 Writes
+		///     - Reads: Vesting Storage, Lifetime Totals, Target Account
+		///     - Writes: Vesting Storage, Lifetime Totals
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::remove_orphaned_vesting())]
+		pub fn remove_orphaned_vesting(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			ensure!(Vesting::<T>::contains_key(&target), Error::<T>::NotVesting);
+			ensure!(T::Currency::total_balance(&target).is_zero(), Error::<T>::NotOrphaned);
+
+			Self::do_remove_vesting_schedule(&target);
+			Ok(Pays::No.into())
+		}
+
+		/// Require `target` to accept the terms of a statement before unlocking any vested funds.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `target`: The account which must accept the terms.
+		/// - `statement_hash`: The hash of the statement which `target` must sign.
+		///
+		/// Emits `TermsRequired`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 0 Reads, 1 Write
+		///     - Writes: Pending Terms
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::require_terms())]
+		pub fn require_terms(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			statement_hash: T::Hash,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target)?;
+
+			PendingTerms::<T>::insert(&target, statement_hash);
+			Self::deposit_event(Event::<T>::TermsRequired { account: target, statement_hash });
+			Ok(())
+		}
+
+		/// Accept the terms of the statement the sender is required to accept, allowing vested
+		/// funds to be unlocked.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `statement_hash_signature`: The sender's signature of the pending statement hash.
+		///
+		/// Emits `TermsAccepted`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Read, 1 Write
+		///     - Reads: Pending Terms
+		///     - Writes: Pending Terms
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::accept_terms())]
+		pub fn accept_terms(
+			origin: OriginFor<T>,
+			statement_hash_signature: T::OffchainSignature,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let statement_hash = PendingTerms::<T>::get(&who).ok_or(Error::<T>::NoTermsPending)?;
+			ensure!(
+				statement_hash_signature.verify(statement_hash.as_ref(), &who),
+				Error::<T>::InvalidStatementSignature,
+			);
+
+			PendingTerms::<T>::remove(&who);
+			Self::deposit_event(Event::<T>::TermsAccepted { account: who });
+			Ok(())
+		}
+
+		/// Lift the terms `target` is required to accept, e.g. as `target` can't sign them, allowing
+		/// its vested funds to be unlocked.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `target`: The account required to accept terms.
+		///
+		/// Emits `TermsCleared`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Read, 1 Write
+		///     - Reads: Pending Terms
+		///     - Writes: Pending Terms
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::clear_terms())]
+		pub fn clear_terms(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			ensure!(PendingTerms::<T>::contains_key(&target), Error::<T>::NoTermsPending);
+
+			PendingTerms::<T>::remove(&target);
+			Self::deposit_event(Event::<T>::TermsCleared { account: target });
+			Ok(())
+		}
+
+		/// Create a standing order making a vested transfer from the sender every `interval`
+		/// blocks, `count` times.
+		///
+		/// The dispatch origin for this call must be `VestedTransferOrigin`.
+		///
+		/// - `target`: The account receiving the transfers.
+		/// - `locked`: The amount transferred and locked by each transfer.
+		/// - `per_block`: The amount of each transfer that gets unlocked every block.
+		/// - `delay`: The number of blocks after each transfer that its funds start vesting.
+		/// - `interval`: The number of blocks between transfers, the first being made `interval`
+		///   blocks from now.
+		/// - `count`: The number of transfers to make.
+		///
+		/// Each transfer is subject to the same checks as `vested_transfer`; a transfer which
+		/// fails, e.g. because `target` is still vesting, is skipped.
+		///
+		/// Emits `StandingOrderCreated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 2 Reads, 3 Writes
+		///     - Reads: Standing Order Count, Scheduler Agenda
+		///     - Writes: Standing Order Count, Standing Orders, Scheduler Agenda
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::create_standing_order())]
+		pub fn create_standing_order(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			locked: BalanceOf<T>,
+			per_block: BalanceOf<T>,
+			delay: T::BlockNumber,
+			interval: T::BlockNumber,
+			count: u32,
+		) -> DispatchResult {
+			let funder = T::VestedTransferOrigin::ensure_origin(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			let starting_block = <frame_system::Pallet<T>>::block_number().saturating_add(delay);
+			Self::ensure_min_vested_transfer(
+				&VestingInfo { locked, per_block, starting_block },
+				false,
+			)?;
+			ensure!(
+				!per_block.is_zero() && !interval.is_zero() && count > 0,
+				Error::<T>::InvalidScheduleParams,
+			);
+			let target = T::Lookup::lookup(target)?;
+
+			let index = StandingOrderCount::<T>::get();
+			let task = Self::schedule_standing_order(&funder, index, interval, count)?;
+			StandingOrderCount::<T>::put(index.saturating_add(1));
+			StandingOrders::<T>::insert(&funder, index, StandingOrder {
+				target: target.clone(),
+				locked,
+				per_block,
+				delay,
+				interval,
+				remaining: count,
+				task,
+			});
+
+			Self::deposit_event(Event::<T>::StandingOrderCreated { funder, index, target });
+			Ok(())
+		}
+
+		/// Change the transfers made by one of the sender's standing orders. The transfers are
+		/// still made at the same blocks.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `index`: The index of the standing order.
+		/// - `target`, `locked`, `per_block`, `delay`: As in `create_standing_order`.
+		///
+		/// Emits `StandingOrderModified`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Read, 1 Write
+		///     - Reads: Standing Orders
+		///     - Writes: Standing Orders
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::modify_standing_order())]
+		pub fn modify_standing_order(
+			origin: OriginFor<T>,
+			#[pallet::compact] index: u32,
+			target: <T::Lookup as StaticLookup>::Source,
+			locked: BalanceOf<T>,
+			per_block: BalanceOf<T>,
+			delay: T::BlockNumber,
+		) -> DispatchResult {
+			let funder = ensure_signed(origin)?;
+			let starting_block = <frame_system::Pallet<T>>::block_number().saturating_add(delay);
+			Self::ensure_min_vested_transfer(
+				&VestingInfo { locked, per_block, starting_block },
+				false,
+			)?;
+			ensure!(!per_block.is_zero(), Error::<T>::InvalidScheduleParams);
+			let target = T::Lookup::lookup(target)?;
+
+			StandingOrders::<T>::try_mutate(&funder, index, |maybe_order| -> DispatchResult {
+				let order = maybe_order.as_mut().ok_or(Error::<T>::UnknownStandingOrder)?;
+				order.target = target;
+				order.locked = locked;
+				order.per_block = per_block;
+				order.delay = delay;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::StandingOrderModified { funder, index });
+			Ok(())
+		}
+
+		/// Cancel one of the sender's standing orders. No further transfers are made.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `index`: The index of the standing order.
+		///
+		/// Emits `StandingOrderCancelled`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 2 Reads, 2 Writes
+		///     - Reads: Standing Orders, Scheduler Agenda
+		///     - Writes: Standing Orders, Scheduler Agenda
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::cancel_standing_order())]
+		pub fn cancel_standing_order(
+			origin: OriginFor<T>,
+			#[pallet::compact] index: u32,
+		) -> DispatchResult {
+			let funder = ensure_signed(origin)?;
+			let order = StandingOrders::<T>::get(&funder, index)
+				.ok_or(Error::<T>::UnknownStandingOrder)?;
+			T::Scheduler::cancel(order.task).map_err(|_| Error::<T>::UnknownStandingOrder)?;
+			StandingOrders::<T>::remove(&funder, index);
+
+			Self::deposit_event(Event::<T>::StandingOrderCancelled { funder, index });
+			Ok(())
+		}
+
+		/// Make the next transfer of a standing order.
+		///
+		/// The dispatch origin for this call must be _Root_. It is dispatched by the scheduler on
+		/// behalf of the standing order.
+		///
+		/// Emits `StandingOrderExecuted`, along with the events of the transfer if it succeeds.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 6 Reads, 6 Writes
+		///     - Reads: Standing Orders, Vesting Storage, Lifetime Totals, Balances Locks, Funder
+		///       Account, Target Account
+		///     - Writes: Standing Orders, Vesting Storage, Lifetime Totals, Balances Locks, Funder
+		///       Account, Target Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::execute_standing_order(MaxLocksOf::<T>::get()))]
+		pub fn execute_standing_order(
+			origin: OriginFor<T>,
+			funder: T::AccountId,
+			#[pallet::compact] index: u32,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let mut order = StandingOrders::<T>::get(&funder, index)
+				.ok_or(Error::<T>::UnknownStandingOrder)?;
+
+			let starting_block =
+				<frame_system::Pallet<T>>::block_number().saturating_add(order.delay);
+			let schedule = VestingInfo {
+				locked: order.locked,
+				per_block: order.per_block,
+				starting_block,
+			};
+			let result = with_transaction(|| {
+				let result = Self::do_vested_transfer(&funder, &order.target, schedule, false);
+				match result {
+					Ok(()) => TransactionOutcome::Commit(result),
+					Err(_) => TransactionOutcome::Rollback(result),
+				}
+			});
+
+			order.remaining = order.remaining.saturating_sub(1);
+			if order.remaining == 0 {
+				StandingOrders::<T>::remove(&funder, index);
+			} else {
+				StandingOrders::<T>::insert(&funder, index, order);
+			}
+
+			Self::deposit_event(Event::<T>::StandingOrderExecuted { funder, index, result });
+			Ok(())
+		}
+
+		/// Stream `amount` from the sender to `target`, paying out `per_block` every block after
+		/// `starting_block`.
+		///
+		/// The dispatch origin for this call must be _Signed_. `amount` is reserved from the sender
+		/// until it is streamed.
+		///
+		/// - `target`: The account receiving the stream.
+		/// - `amount`: The total amount to stream.
+		/// - `per_block`: The amount streamed every block.
+		/// - `starting_block`: The block after which funds start streaming.
+		///
+		/// Emits `StreamCreated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 2 Reads, 3 Writes
+		///     - Reads: Stream Count, Sender Account
+		///     - Writes: Stream Count, Streams, Sender Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::create_stream())]
+		pub fn create_stream(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			amount: BalanceOf<T>,
+			per_block: BalanceOf<T>,
+			starting_block: T::BlockNumber,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				!amount.is_zero() && !per_block.is_zero(),
+				Error::<T>::InvalidScheduleParams,
+			);
+			let target = T::Lookup::lookup(target)?;
+
+			T::Currency::reserve_named(&T::ReserveId::get(), &sender, amount)?;
+			let index = StreamCount::<T>::get();
+			StreamCount::<T>::put(index.saturating_add(1));
+			Streams::<T>::insert(index, Stream {
+				sender: sender.clone(),
+				recipient: target.clone(),
+				amount,
+				per_block,
+				starting_block,
+				withdrawn: Zero::zero(),
+			});
+
+			Self::deposit_event(Event::<T>::StreamCreated { index, sender, recipient: target });
+			Ok(())
+		}
+
+		/// Withdraw everything streamed to the sender so far.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the recipient of the stream.
+		///
+		/// - `index`: The index of the stream.
+		///
+		/// Emits `StreamWithdrawn`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 3 Writes
+		///     - Reads: Streams, Sender Account, Recipient Account
+		///     - Writes: Streams, Sender Account, Recipient Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::withdraw_from_stream())]
+		pub fn withdraw_from_stream(
+			origin: OriginFor<T>,
+			#[pallet::compact] index: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut stream = Streams::<T>::get(index).ok_or(Error::<T>::UnknownStream)?;
+			ensure!(stream.recipient == who, Error::<T>::NotStreamParty);
+
+			let amount = Self::pay_out_stream(&mut stream)?;
+			if stream.withdrawn == stream.amount {
+				Streams::<T>::remove(index);
+			} else {
+				Streams::<T>::insert(index, stream);
+			}
+
+			Self::deposit_event(Event::<T>::StreamWithdrawn { index, amount });
+			Ok(())
+		}
+
+		/// Cancel a stream funded by the sender. Whatever has been streamed so far is paid out to
+		/// the recipient and the rest is returned to the sender. Streamed funds can only be paid
+		/// out to a recipient account which exists; if they can't be paid out, the stream is kept
+		/// without streaming any more so that the recipient may withdraw them later.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the sender of the stream.
+		///
+		/// - `index`: The index of the stream.
+		///
+		/// Emits `StreamCancelled`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 3 Writes
+		///     - Reads: Streams, Sender Account, Recipient Account
+		///     - Writes: Streams, Sender Account, Recipient Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::cancel_stream())]
+		pub fn cancel_stream(
+			origin: OriginFor<T>,
+			#[pallet::compact] index: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut stream = Streams::<T>::get(index).ok_or(Error::<T>::UnknownStream)?;
+			ensure!(stream.sender == who, Error::<T>::NotStreamParty);
+
+			let streamed = stream.streamed_at(<frame_system::Pallet<T>>::block_number());
+			let payout = Self::pay_out_stream(&mut stream);
+			let refunded = stream.amount.saturating_sub(streamed);
+			let leftover =
+				T::Currency::unreserve_named(&T::ReserveId::get(), &stream.sender, refunded);
+			let refunded = refunded.saturating_sub(leftover);
+			let paid_out = match payout {
+				Ok(paid_out) => {
+					Streams::<T>::remove(index);
+					paid_out
+				},
+				Err(_) => {
+					stream.amount = streamed;
+					Streams::<T>::insert(index, stream);
+					Zero::zero()
+				},
+			};
+
+			Self::deposit_event(Event::<T>::StreamCancelled { index, paid_out, refunded });
+			Ok(())
+		}
+
+		/// Create a vesting position for `beneficiary`, represented by an NFT which they own.
+		///
+		/// The dispatch origin for this call must be _Signed_. `schedule.locked` is reserved from
+		/// the sender until it vests. Vested funds are paid out to the owner of the NFT at the time,
+		/// so the position may be transferred along with it.
+		///
+		/// - `beneficiary`: The account the NFT is minted to.
+		/// - `schedule`: The vesting schedule of the position.
+		///
+		/// Emits `PositionCreated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 4 Writes
+		///     - Reads: Position Count, Sender Account, Positions NFT
+		///     - Writes: Position Count, Positions, Sender Account, Positions NFT
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::create_position())]
+		pub fn create_position(
+			origin: OriginFor<T>,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			let funder = ensure_signed(origin)?;
+			Self::ensure_min_vested_transfer(&schedule, false)?;
+			ensure!(!schedule.per_block.is_zero(), Error::<T>::InvalidScheduleParams);
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			let index = PositionCount::<T>::get();
+			let id = T::ReserveId::get();
+			with_transaction(|| {
+				let result = T::Currency::reserve_named(&id, &funder, schedule.locked)
+					.and_then(|_| T::Positions::mint_into(&T::PositionClass::get(), &index, &beneficiary));
+				match result {
+					Ok(()) => TransactionOutcome::Commit(result),
+					Err(_) => TransactionOutcome::Rollback(result),
+				}
+			})?;
+			PositionCount::<T>::put(index.saturating_add(1));
+			Positions::<T>::insert(index, Position {
+				funder: funder.clone(),
+				schedule: Self::on_clock(schedule),
+				paid_out: Zero::zero(),
+			});
+
+			Self::deposit_event(Event::<T>::PositionCreated { index, funder, beneficiary });
+			Ok(())
+		}
+
+		/// Pay out the vested funds of a position to the current owner of its NFT. Once fully paid
+		/// out, the NFT is burned.
+		///
+		/// Only what is left of the funder's reserve for positions is paid out, should it have
+		/// been slashed.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `index`: The index of the position.
+		///
+		/// Emits `PositionVested`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 4 Writes
+		///     - Reads: Positions, Positions NFT, Funder Account, Owner Account
+		///     - Writes: Positions, Positions NFT, Funder Account, Owner Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vest_position())]
+		pub fn vest_position(
+			origin: OriginFor<T>,
+			#[pallet::compact] index: u32,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let mut position = Positions::<T>::get(index).ok_or(Error::<T>::UnknownPosition)?;
+			let class = T::PositionClass::get();
+			let owner = T::Positions::owner(&class, &index).ok_or(Error::<T>::UnknownPosition)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let vested = position.schedule.locked
+				.saturating_sub(Self::locked_under(&position.schedule, now));
+			let due = vested.saturating_sub(position.paid_out);
+			let amount = with_transaction(|| {
+				let result = Self::pay_out_position(index, &mut position, &owner, due);
+				match result {
+					Ok(_) => TransactionOutcome::Commit(result),
+					Err(_) => TransactionOutcome::Rollback(result),
+				}
+			})?;
+
+			Self::deposit_event(Event::<T>::PositionVested { index, owner, amount });
+			Ok(())
+		}
+
+		/// Create a vesting plan from which vested transfers may derive their schedules.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `plan`: The plan to create. Its duration must not be zero.
+		///
+		/// Emits `VestingPlanCreated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Read, 2 Writes
+		///     - Reads: Vesting Plan Count
+		///     - Writes: Vesting Plan Count, Vesting Plans
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::create_vesting_plan())]
+		pub fn create_vesting_plan(
+			origin: OriginFor<T>,
+			plan: VestingPlan<T::BlockNumber>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(!plan.duration.is_zero(), Error::<T>::InvalidScheduleParams);
+
+			let index = VestingPlanCount::<T>::get();
+			VestingPlanCount::<T>::put(index.saturating_add(1));
+			VestingPlans::<T>::insert(index, plan);
+
+			Self::deposit_event(Event::<T>::VestingPlanCreated { index });
+			Ok(())
+		}
+
+		/// Create a vested transfer whose schedule is derived from a vesting plan.
+		///
+		/// The dispatch origin for this call must be `VestedTransferOrigin`.
+		///
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `plan`: The index of the vesting plan.
+		/// - `amount`: The amount of funds to transfer and will be vested.
+		///
+		/// Funds start vesting `cliff` blocks from now and are fully vested `duration` blocks
+		/// later.
+		///
+		/// Emits `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 5 Reads, 5 Writes
+		///     - Reads: Vesting Plans, Vesting Storage, Lifetime Totals, Balances Locks, Target
+		///       Account, [Sender Account]
+		///     - Writes: Plan Of, Vesting Storage, Lifetime Totals, Balances Locks, Target Account,
+		///       [Sender Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vested_transfer_with_plan(MaxLocksOf::<T>::get()))]
+		pub fn vested_transfer_with_plan(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] plan: u32,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let transactor = T::VestedTransferOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			let template = VestingPlans::<T>::get(plan).ok_or(Error::<T>::UnknownPlan)?;
+
+			let duration = block_number_to_balance::<_, BalanceOf<T>>(template.duration).max(One::one());
+			let schedule = VestingInfo {
+				locked: amount,
+				per_block: (amount / duration).max(One::one()),
+				starting_block: <frame_system::Pallet<T>>::block_number()
+					.saturating_add(template.cliff),
+			};
+			Self::do_vested_transfer(&transactor, &target, schedule, false)?;
+			// Schedules which have already ended are removed straight away.
+			if !Vesting::<T>::contains_key(&target) {
+				return Ok(())
+			}
+			PlanOf::<T>::insert(&target, plan);
+			PlanMembers::<T>::insert(plan, &target, ());
+			Ok(())
+		}
+
+		/// Create a vested transfer whose schedule is labelled with `metadata`, e.g. the
+		/// distribution it belongs to, so that indexers and revocation tooling can filter by it.
+		/// The metadata is kept until the schedule ends.
+		///
+		/// The dispatch origin for this call must be `VestedTransferOrigin`.
+		///
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		/// - `metadata`: The label of the schedule, of at most `MaxMetadataLen` bytes.
+		///
+		/// Emits `VestingMetadataSet`, unless the schedule has already ended.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 5 Writes
+		///     - Reads: Vesting Storage, Lifetime Totals, Balances Locks, Target Account, [Sender
+		///       Account]
+		///     - Writes: Schedule Metadata, Vesting Storage, Lifetime Totals, Balances Locks,
+		///       Target Account, [Sender Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vested_transfer_with_metadata(MaxLocksOf::<T>::get()))]
+		pub fn vested_transfer_with_metadata(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+			metadata: BoundedVec<u8, T::MaxMetadataLen>,
+		) -> DispatchResult {
+			let transactor = T::VestedTransferOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(&transactor, &target, schedule, false)?;
+			// Schedules which have already ended are removed straight away.
+			if !Vesting::<T>::contains_key(&target) {
+				return Ok(())
+			}
+			ScheduleMetadata::<T>::insert(&target, &metadata);
+			let metadata = metadata.into_inner();
+			Self::deposit_event(Event::<T>::VestingMetadataSet { account: target, metadata });
+			Ok(())
+		}
+
+		/// Revoke the vesting schedules derived from a revocable plan, transferring their unvested
+		/// funds to `destination`. The plan is removed so that no further schedules are derived
+		/// from it.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `plan`: The index of the vesting plan.
+		/// - `destination`: The account receiving the unvested funds.
+		/// - `limit`: The maximum number of schedules to revoke. Plans with more schedules are
+		///   revoked over several calls, `VestingPlanRevoked` being emitted once done.
+		///
+		/// Emits `VestingRevoked` or `VestingRevocationFailed` for each schedule.
+		///
+		/// # <weight>
+		/// - `O(L)` where `L` is `limit`.
+		/// - DbWeight: 1 + 5 * L Reads, 1 + 6 * L Writes
+		///     - Reads: Vesting Plans, and per schedule: Plan Members, Vesting Storage, Balances
+		///       Locks, Target Account, Destination Account
+		///     - Writes: Vesting Plans, and per schedule: Plan Members, Plan Of, Vesting Storage,
+		///       Lifetime Totals, Balances Locks, Target Account, Destination Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::revoke_vesting_plan(*limit))]
+		pub fn revoke_vesting_plan(
+			origin: OriginFor<T>,
+			#[pallet::compact] plan: u32,
+			destination: <T::Lookup as StaticLookup>::Source,
+			limit: u32,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let destination = T::Lookup::lookup(destination)?;
+			match VestingPlans::<T>::get(plan) {
+				Some(template) => {
+					ensure!(template.revocable, Error::<T>::PlanNotRevocable);
+					VestingPlans::<T>::remove(plan);
+				},
+				// Only revocable plans are removed, so the members of a plan which no longer
+				// exists are left over from an earlier call.
+				None => ensure!(
+					PlanMembers::<T>::iter_prefix(plan).next().is_some(),
+					Error::<T>::UnknownPlan,
+				),
+			}
+
+			let members: Vec<_> = PlanMembers::<T>::iter_key_prefix(plan)
+				.take(limit as usize)
+				.collect();
+			for who in members {
+				Self::revoke_vesting(plan, &who, &destination);
+			}
+
+			if PlanMembers::<T>::iter_prefix(plan).next().is_none() {
+				Self::deposit_event(Event::<T>::VestingPlanRevoked { plan });
+			}
+			Ok(())
+		}
+
+		/// Designate an account which may claim the sender's vesting funds should the sender not
+		/// vest them for `ContingencyPeriod` blocks after their schedule has fully matured.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this pallet.
+		///
+		/// - `contingent`: The contingent beneficiary, or `None` to clear it.
+		///
+		/// Emits `ContingentBeneficiarySet`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Read, 1 Write
+		///     - Read: Vesting Storage
+		///     - Write: Contingent Beneficiary
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::set_contingent_beneficiary())]
+		pub fn set_contingent_beneficiary(
+			origin: OriginFor<T>,
+			contingent: Option<<T::Lookup as StaticLookup>::Source>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Vesting::<T>::contains_key(&who), Error::<T>::NotVesting);
+			let contingent = contingent.map(T::Lookup::lookup).transpose()?;
+			ContingentBeneficiary::<T>::mutate_exists(&who, |c| *c = contingent.clone());
+			Self::deposit_event(Event::<T>::ContingentBeneficiarySet { account: who, contingent });
+			Ok(())
+		}
+
+		/// Route a fraction of the funds the sender unlocks under its schedule to another
+		/// account, e.g. for tax withholding or to a charity, each time it vests.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this pallet. The withholding is cleared along with the schedule.
+		///
+		/// - `withholding`: The account receiving the withheld funds and the fraction of each
+		///   unlocked amount withheld, or `None` to clear it.
+		///
+		/// Emits `WithholdingSet`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Read, 1 Write
+		///     - Read: Vesting Storage
+		///     - Write: Withholding
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::set_withholding())]
+		pub fn set_withholding(
+			origin: OriginFor<T>,
+			withholding: Option<(<T::Lookup as StaticLookup>::Source, Perbill)>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Vesting::<T>::contains_key(&who), Error::<T>::NotVesting);
+			let withholding = withholding
+				.map(|(destination, rate)| T::Lookup::lookup(destination).map(|d| (d, rate)))
+				.transpose()?;
+			Withholding::<T>::mutate_exists(&who, |w| *w = withholding.clone());
+			Self::deposit_event(Event::<T>::WithholdingSet { account: who, withholding });
+			Ok(())
+		}
+
+		/// Claim the funds still locked under the vesting schedule of an account which has
+		/// designated the sender as its contingent beneficiary.
+		///
+		/// Vesting a fully matured schedule completes it, so the account is considered inactive
+		/// once its schedule has been fully matured for `ContingencyPeriod` blocks without it
+		/// vesting. The funds are unlocked and transferred to the sender, reaping the account if
+		/// left with less than the existential deposit. As when vesting, the account must meet
+		/// `VestPrecondition`, the `UnlockOracle` must allow unlocking and no terms may be pending.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `who`: The inactive vesting account.
+		///
+		/// Emits `VestingCompleted` and `ContingentClaimed`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 5 Reads, 6 Writes
+		///     - Reads: Contingent Beneficiary, Vesting Storage, Lifetime Totals, Balances Locks,
+		///       Target Account
+		///     - Writes: Contingent Beneficiary, Vesting Storage, Lifetime Totals, Balances Locks,
+		///       Target Account, Sender Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::claim_as_contingent(MaxLocksOf::<T>::get()))]
+		pub fn claim_as_contingent(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let contingent = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(
+				Self::contingent_beneficiary(&who).as_ref() == Some(&contingent),
+				Error::<T>::NotContingentBeneficiary,
+			);
+			let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			ensure!(
+				Self::matured_for(&vesting, T::ContingencyPeriod::get()),
+				Error::<T>::ContingencyPeriodNotElapsed,
+			);
+
+			let amount = Self::complete_abandoned(&who, &vesting, Some(&contingent))?;
+			Self::deposit_event(Event::<T>::ContingentClaimed { account: who, contingent, amount });
+			Ok(())
+		}
+
+		/// Sweep the vesting schedule of an account which has not vested it for `SweepDelay`
+		/// blocks after it fully matured, unlocking the funds and moving them to
+		/// `SweepDestination` if there is one. Schedules are only swept while their funds could be
+		/// unlocked by vesting, i.e. the account meets `VestPrecondition`, the `UnlockOracle`
+		/// allows it and no terms are pending.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `who`: The account whose schedule should be swept.
+		///
+		/// Emits `VestingCompleted` and `VestingSwept`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 6 Writes
+		///     - Reads: Vesting Storage, Lifetime Totals, Balances Locks, Target Account
+		///     - Writes: Vesting Storage, Lifetime Totals, Bonus Paid Until, Balances Locks,
+		///       Target Account, [Destination Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::sweep(MaxLocksOf::<T>::get()))]
+		pub fn sweep(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let delay = T::SweepDelay::get().ok_or(Error::<T>::SweepingDisabled)?;
+			let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			ensure!(Self::matured_for(&vesting, delay), Error::<T>::NotSweepable);
+
+			let destination = T::SweepDestination::get();
+			let amount = Self::complete_abandoned(&who, &vesting, destination.as_ref())?;
+			Self::deposit_event(Event::<T>::VestingSwept { account: who, amount });
+			Ok(())
+		}
+
+		/// Set the factor by which the rate of unlocking of all vesting schedules is scaled.
+		///
+		/// The multiplier applies from the current block on, by changing the rate of the vesting
+		/// clock: the blocks which have already passed keep counting toward vesting at the
+		/// multiplier in effect at the time.
+		///
+		/// The dispatch origin for this call must be `UnlockMultiplierOrigin`.
+		///
+		/// - `multiplier`: The new unlock multiplier, e.g. 1.5 to unlock funds half as fast again.
+		///   Must be greater than zero and at most `MaxUnlockMultiplier`; progression is frozen
+		///   with `freeze_progression` instead.
+		///
+		/// Emits `UnlockMultiplierSet`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 2 Writes
+		///     - Reads: Vesting Clock, Unlock Multiplier, Progression Frozen Since
+		///     - Writes: Vesting Clock, Unlock Multiplier
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::set_unlock_multiplier())]
+		pub fn set_unlock_multiplier(origin: OriginFor<T>, multiplier: FixedU128) -> DispatchResult {
+			T::UnlockMultiplierOrigin::ensure_origin(origin)?;
+			ensure!(
+				!multiplier.is_zero() && multiplier <= T::MaxUnlockMultiplier::get(),
+				Error::<T>::InvalidUnlockMultiplier,
+			);
+			Self::checkpoint_clock();
+			UnlockMultiplier::<T>::put(multiplier);
+			Self::deposit_event(Event::<T>::UnlockMultiplierSet { multiplier });
+			Ok(())
+		}
+
+		/// Approve `vester` to call `vest_other` for the sender when `PermissionedVestOther` is
+		/// set.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Emits `VesterApproved`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Write
+		///     - Write: Approved Vesters
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::approve_vester())]
+		pub fn approve_vester(
+			origin: OriginFor<T>,
+			vester: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vester = T::Lookup::lookup(vester)?;
+			ApprovedVesters::<T>::insert(&who, &vester, ());
+			Self::deposit_event(Event::<T>::VesterApproved { account: who, vester });
+			Ok(())
+		}
+
+		/// Revoke the sender's approval of `vester` to call `vest_other` for it.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Emits `VesterRevoked`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Write
+		///     - Write: Approved Vesters
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::revoke_vester())]
+		pub fn revoke_vester(
+			origin: OriginFor<T>,
+			vester: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vester = T::Lookup::lookup(vester)?;
+			ApprovedVesters::<T>::remove(&who, &vester);
+			Self::deposit_event(Event::<T>::VesterRevoked { account: who, vester });
+			Ok(())
+		}
+
+		/// Pause the creation of vesting schedules, e.g. during an incident. Funds keep vesting
+		/// and may still be unlocked.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// Emits `SchedulesPaused`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Write
+		///     - Write: Paused
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::pause())]
+		pub fn pause(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			Paused::<T>::put(true);
+			Self::deposit_event(Event::<T>::SchedulesPaused);
+			Ok(())
+		}
+
+		/// Resume the creation of vesting schedules.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// Emits `SchedulesUnpaused`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Write
+		///     - Write: Paused
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::unpause())]
+		pub fn unpause(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			Paused::<T>::kill();
+			Self::deposit_event(Event::<T>::SchedulesUnpaused);
+			Ok(())
+		}
+
+		/// Freeze the progression of all vesting schedules, e.g. during a security incident, so
+		/// that no more funds are unlocked until it is resumed. Unlike `pause`, this doesn't stop
+		/// the creation of schedules.
+		///
+		/// The vesting clock stands still while progression is frozen, so the blocks for which it
+		/// is don't count toward vesting: the start and end of every schedule are pushed back by
+		/// as long once it is resumed.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// Emits `ProgressionFrozen`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 2 Writes
+		///     - Read: Progression Frozen Since, Vesting Clock, Unlock Multiplier
+		///     - Write: Progression Frozen Since, Vesting Clock
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::freeze_progression())]
+		pub fn freeze_progression(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(
+				Self::progression_frozen_since().is_none(),
+				Error::<T>::ProgressionAlreadyFrozen,
+			);
+			let at = <frame_system::Pallet<T>>::block_number();
+			Self::checkpoint_clock();
+			ProgressionFrozenSince::<T>::put(at);
+			Self::deposit_event(Event::<T>::ProgressionFrozen { at });
+			Ok(())
+		}
+
+		/// Resume the progression of vesting frozen by `freeze_progression`.
+		///
+		/// The blocks for which it was frozen are added to `TotalFrozen`, so that progression may
+		/// be frozen any number of times.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// Emits `ProgressionResumed`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 3 Writes
+		///     - Read: Progression Frozen Since, Vesting Clock, Total Frozen
+		///     - Write: Progression Frozen Since, Vesting Clock, Total Frozen
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::resume_progression())]
+		pub fn resume_progression(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let frozen = Self::progression_frozen_since().ok_or(Error::<T>::ProgressionNotFrozen)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			// The clock stands still up to now.
+			Self::checkpoint_clock();
+			ProgressionFrozenSince::<T>::kill();
+			let frozen_for = now.saturating_sub(frozen);
+			TotalFrozen::<T>::mutate(|total| *total = total.saturating_add(frozen_for));
+			Self::deposit_event(Event::<T>::ProgressionResumed { frozen_for });
+			Ok(())
+		}
+
+		/// Set the minimum amount transferred to call `vested_transfer`, e.g. to track the price
+		/// of the token.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// Emits `MinVestedTransferSet`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Write
+		///     - Write: Minimum Vested Transfer
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::set_min_vested_transfer())]
+		pub fn set_min_vested_transfer(
+			origin: OriginFor<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			MinimumVestedTransfer::<T>::put(amount);
+			Self::deposit_event(Event::<T>::MinVestedTransferSet { amount });
+			Ok(())
+		}
+
+		/// Export the vesting schedule of `who` so that it can be carried over to another chain,
+		/// e.g. when moving to a parachain.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// The schedule is removed along with its lock. What remains of it is emitted, starting
+		/// no earlier than the current block, to be passed to `import_schedule` on the other
+		/// chain. The funds it covers are expected to be moved by the same migration.
+		///
+		/// Emits `VestingExported`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 9 Writes
+		///     - Reads: Vesting Storage, Unlock Multiplier, Schedule Deposits, Balances Locks
+		///     - Writes: Vesting Storage, Schedules Ending At, Lifetime Totals, Bonus Paid Until,
+		///       Contingent Beneficiary, Schedule Deposits, Plan Of, Funded By, Balances Locks
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::export_schedule(MaxLocksOf::<T>::get()))]
+		pub fn export_schedule(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			let locked = Self::locked_under(&vesting, now);
+			ensure!(!locked.is_zero(), Error::<T>::NotVesting);
+
+			Self::do_remove_vesting_schedule(&who);
+			let starting_block = if vesting.starting_block > Self::clock_at(now) {
+				Self::block_reaching(vesting.starting_block).unwrap_or(now)
+			} else {
+				now
+			};
+			let remaining = VestingInfo { locked, per_block: vesting.per_block, starting_block };
+			Self::deposit_event(Event::<T>::VestingExported { account: who, remaining });
+			Ok(())
+		}
+
+		/// Import a vesting schedule exported from another chain onto `who`, locking funds it
+		/// already holds.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`, which vouches for the
+		/// schedule having been exported.
+		///
+		/// - `who`: The account receiving the schedule. It must not be vesting already and must
+		/// hold at least `schedule.locked`.
+		/// - `schedule`: The schedule emitted by `export_schedule`.
+		///
+		/// Emits `VestingImported`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 5 Writes
+		///     - Reads: Vesting Storage, Unlock Multiplier, Lifetime Totals, Balances Locks
+		///     - Writes: Vesting Storage, Schedules Ending At, Lifetime Totals, Bonus Paid Until,
+		///       Balances Locks
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::import_schedule(MaxLocksOf::<T>::get()))]
+		pub fn import_schedule(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			Self::apply_vesting_schedule(&who, schedule)?;
+			Self::deposit_event(Event::<T>::VestingImported { account: who, schedule });
+			Ok(())
+		}
+
+		/// Complete the fully vested schedule of `who`, unlocking the rest of their funds.
+		///
+		/// The dispatch origin for this call must be _None_. It is submitted by the offchain
+		/// worker for schedules which have matured, and is only valid while `who`'s schedule is
+		/// fully vested but not yet completed.
+		///
+		/// - `who`: The account whose schedule has matured.
+		///
+		/// Emits `VestingCompleted`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 20 Reads, 22 Writes when completing the schedule
+		///     - Reads: Vesting Storage, Pending Terms, Vesting Clock, Unlock Multiplier,
+		///       Progression Frozen Since, Bonus Paid Until, Withholding, Lifetime Totals,
+		///       Balances Locks, Target Account, Bonus Payout Account, Withholding Account,
+		///       Schedule Deposits, Balances Reserves, Depositor Account, Plan Of, Funded By,
+		///       Auto Vest At, Auto Vest Queue, Completed Vesting
+		///     - Writes: Vesting Storage, Bonus Paid Until, Withholding, Lifetime Totals,
+		///       Balances Locks, Target Account, Bonus Payout Account, Withholding Account,
+		///       Schedules Ending At, Contingent Beneficiary, Schedule Metadata, Schedule Deposits,
+		///       Balances Reserves, Depositor Account, Plan Of, Plan Members, Funded By,
+		///       Funded Schedules, Self Imposed, Auto Vest At, Auto Vest Queue, Completed Vesting
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))]
+		pub fn vest_matured(origin: OriginFor<T>, who: T::AccountId) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			ensure!(Self::is_matured(&who), Error::<T>::NotMatured);
+			Self::do_vest(who)
+		}
+
+		/// Impose a vesting schedule on funds `who` already holds, without transferring any.
+		///
+		/// This is meant for lockups imposed by governance, e.g. on returned exploit funds or
+		/// as a penalty escrow.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `who`: The account whose funds should be locked. It must not be vesting already and
+		/// must hold at least `schedule.locked`.
+		/// - `schedule`: The schedule to lock the funds under.
+		///
+		/// Emits `VestingApplied`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 5 Writes
+		///     - Reads: Vesting Storage, Unlock Multiplier, Lifetime Totals, Balances Locks
+		///     - Writes: Vesting Storage, Schedules Ending At, Lifetime Totals, Bonus Paid Until,
+		///       Balances Locks
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::force_apply_vesting(MaxLocksOf::<T>::get()))]
+		pub fn force_apply_vesting(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			Self::apply_vesting_schedule(&who, schedule)?;
+			Self::deposit_event(Event::<T>::VestingApplied { account: who, schedule });
+			Ok(())
+		}
+
+		/// Place some of the sender's own free balance under a vesting schedule, e.g. to commit
+		/// to saving it.
+		///
+		/// The dispatch origin for this call must be _Signed_. The sender must not be vesting
+		/// already. The schedule is handled like any other, and can't be undone by the sender.
+		///
+		/// - `schedule`: The schedule to lock the sender's funds under. It must lock at least
+		/// `MinVestedTransfer`, and no more than the sender's free balance.
+		///
+		/// Emits `VestingSelfImposed`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 6 Writes
+		///     - Reads: Vesting Storage, Unlock Multiplier, Lifetime Totals, Balances Locks
+		///     - Writes: Vesting Storage, Schedules Ending At, Lifetime Totals, Bonus Paid Until,
+		///       Self Imposed, Balances Locks
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vest_own_balance(MaxLocksOf::<T>::get()))]
+		pub fn vest_own_balance(
+			origin: OriginFor<T>,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			Self::ensure_min_vested_transfer(&schedule, false)?;
+			Self::apply_vesting_schedule(&who, schedule)?;
+			SelfImposed::<T>::insert(&who, ());
+			Self::deposit_event(Event::<T>::VestingSelfImposed { account: who, schedule });
+			Ok(())
+		}
+
+		/// Leave the sender's self-imposed schedule early, unlocking all of their funds at once
+		/// but forfeiting `EarlyExitPenalty` of what is still unvested.
+		///
+		/// The dispatch origin for this call must be _Signed_, by an account whose schedule was
+		/// placed with `vest_own_balance`. The penalty is handed to `EarlyExitPenaltyHandler`.
+		///
+		/// Emits `VestingExitedEarly`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 6 Reads, 7 Writes
+		///     - Reads: Vesting Storage, Self Imposed, Unlock Multiplier, Lifetime Totals,
+		///       Balances Locks, Sender Account
+		///     - Writes: Vesting Storage, Schedules Ending At, Lifetime Totals, Bonus Paid Until,
+		///       Self Imposed, Balances Locks, Sender Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::exit_early(MaxLocksOf::<T>::get()))]
+		pub fn exit_early(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			ensure!(SelfImposed::<T>::contains_key(&who), Error::<T>::NotSelfImposed);
+			let now = <frame_system::Pallet<T>>::block_number();
+			let penalty = T::EarlyExitPenalty::get() * Self::locked_under(&vesting, now);
+
+			with_transaction(|| {
+				Self::do_remove_vesting_schedule(&who);
+				let withdrawn = T::Currency::withdraw(
+					&who,
+					penalty,
+					WithdrawReasons::TRANSFER,
+					ExistenceRequirement::AllowDeath,
+				);
+				match withdrawn {
+					Ok(imbalance) => {
+						T::EarlyExitPenaltyHandler::on_unbalanced(imbalance);
+						TransactionOutcome::Commit(Ok(()))
+					},
+					Err(e) => TransactionOutcome::Rollback(Err(e)),
+				}
+			})?;
+			Self::deposit_event(Event::<T>::VestingExitedEarly { account: who, penalty });
+			Ok(())
+		}
+
+		/// Set the Merkle root of the `(account, schedule)` allocations which may be claimed with
+		/// `claim_vesting`, or clear it with `None`.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// Each leaf is `claim_leaf(account, schedule)`, and each node is the hash of its two
+		/// children in ascending order. Accounts which claimed under a previous root may claim
+		/// again under a new one.
+		///
+		/// Emits `ClaimsRootSet`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 1 Write
+		///     - Write: Claims Root
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::set_claims_root())]
+		pub fn set_claims_root(origin: OriginFor<T>, root: Option<T::Hash>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ClaimsRoot::<T>::set(root);
+			Self::deposit_event(Event::<T>::ClaimsRootSet { root });
+			Ok(())
+		}
+
+		/// Claim the allocation of `who` under the claims root, paying it out to them under
+		/// `schedule`.
+		///
+		/// The dispatch origin for this call must be _Signed_, by any account, so that
+		/// allocations can be claimed on behalf of accounts without funds to pay the fee.
+		///
+		/// - `who`: The account whose allocation is claimed. It must not be vesting already.
+		/// - `schedule`: The schedule of the allocation, which pays out `schedule.locked`.
+		/// - `proof`: The siblings of the allocation's leaf on its path to the claims root, from
+		/// the bottom up.
+		///
+		/// Emits `VestingClaimed`.
+		///
+		/// # <weight>
+		/// - `O(P)` where `P` is the length of the proof.
+		/// - DbWeight: 6 Reads, 7 Writes
+		///     - Reads: Paused, Claims Root, Claimed, Vesting Storage, Unlock Multiplier,
+		///       Balances Locks
+		///     - Writes: Claimed, Vesting Storage, Schedules Ending At, Lifetime Totals,
+		///       Bonus Paid Until, Balances Locks, Target Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::claim_vesting(proof.len() as u32))]
+		pub fn claim_vesting(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+			proof: Vec<T::Hash>,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			ensure!(
+				proof.len() <= T::MaxClaimProofLength::get() as usize,
+				Error::<T>::ClaimProofTooLong,
+			);
+			let root = Self::claims_root().ok_or(Error::<T>::NoClaimsRoot)?;
+			ensure!(!Claimed::<T>::contains_key(root, &who), Error::<T>::AlreadyClaimed);
+			let leaf = Self::claim_leaf(&who, &schedule);
+			ensure!(Self::claim_root_of(leaf, &proof) == root, Error::<T>::InvalidClaimProof);
+			Self::can_add_vesting_schedule(
+				&who,
+				schedule.locked,
+				schedule.per_block,
+				schedule.starting_block,
+			)?;
+
+			T::ClaimsPayout::pay(&who, schedule.locked)?;
+			Claimed::<T>::insert(root, &who, ());
+			Self::insert_vesting_schedule(&who, schedule);
+			Self::deposit_event(Event::<T>::VestingClaimed { account: who, schedule });
+			Ok(())
+		}
+
+		/// Register the sender's schedule to be vested automatically at the block at which it is
+		/// fully vested, so that the funds become usable without any further action.
+		///
+		/// The dispatch origin for this call must be _Signed_ by a vesting account. Its fee
+		/// covers the vesting done later in `on_initialize`. The registration is dropped along
+		/// with the schedule.
+		///
+		/// The ending block is taken at the current unlock multiplier; if the multiplier is
+		/// lowered before then, only what has vested by that block is unlocked.
+		///
+		/// Emits `AutoVestRegistered`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 4 Reads, 2 Writes
+		///     - Reads: Vesting Storage, Auto Vest At, Unlock Multiplier, Auto Vest Queue
+		///     - Writes: Auto Vest At, Auto Vest Queue
+		/// - Plus the weight of `vest_other`, for the vesting in `on_initialize`.
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::register_auto_vest()
+			.saturating_add(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
+		)]
+		pub fn register_auto_vest(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!AutoVestAt::<T>::contains_key(&who), Error::<T>::AlreadyRegistered);
+			let at = Self::auto_vest_block(&who).ok_or(Error::<T>::NotVesting)?;
+
+			AutoVestQueue::<T>::try_append(at, &who).map_err(|_| Error::<T>::AutoVestQueueFull)?;
+			AutoVestAt::<T>::insert(&who, at);
+			Self::deposit_event(Event::<T>::AutoVestRegistered { account: who, at });
+			Ok(())
+		}
+	}
+}
+
+/// Check the explicit vesting `entries` of a chain spec against the `balances` it endows, so that
+/// a launch config can be verified before genesis rather than failing its build.
+///
+/// Every problem found is reported against the account concerned. Schedules which don't lock
+/// funds or vest every block, accounts given more than one schedule, schedules locking more than
+/// the account is endowed with and those locking more than `MaxTotalVested` are rejected by the
+/// genesis build, and reported with the same reasons. Schedules which don't end before the
+/// maximum block number and accounts listed more than once in `balances` are reported as well.
+#[cfg(feature = "std")]
+pub fn validate_genesis_vesting<T: Config>(
+	entries: &[(T::AccountId, VestingInfo<BalanceOf<T>, T::BlockNumber>)],
+	balances: &[(T::AccountId, BalanceOf<T>)],
+) -> Result<(), Vec<(T::AccountId, &'static str)>> {
+	use sp_runtime::traits::CheckedAdd;
+	use std::collections::{btree_map::Entry, BTreeMap};
+
+	let mut invalid = Vec::new();
+	let mut endowed = BTreeMap::new();
+	for (who, balance) in balances {
+		match endowed.entry(who) {
+			Entry::Vacant(entry) => {
+				entry.insert(*balance);
+			},
+			Entry::Occupied(_) => invalid.push((who.clone(), "Duplicate balances in genesis")),
+		}
+	}
+
+	let mut vesting = BTreeMap::new();
+	for (who, schedule) in entries {
+		if !schedule.is_valid() {
+			invalid.push((
+				who.clone(),
+				"Vesting schedules must lock funds and vest some every block",
+			));
+			continue
+		}
+		let blocks: u128 = ((schedule.locked + schedule.per_block - One::one()) / schedule.per_block)
+			.unique_saturated_into();
+		let blocks = T::BlockNumber::unique_saturated_from(blocks);
+		if schedule.starting_block.checked_add(&blocks).is_none() {
+			invalid.push((who.clone(), "Vesting must end before the maximum block number"));
+			continue
+		}
+		if vesting.insert(who, ()).is_some() {
+			invalid.push((who.clone(), "Accounts may only have one vesting schedule"));
+			continue
+		}
+		if endowed.get(who).map_or(true, |balance| *balance < schedule.locked) {
+			invalid.push((who.clone(), "Currencies must be init'd before vesting"));
+			continue
+		}
+		if T::MaxTotalVested::get().map_or(false, |max| schedule.locked > max) {
+			invalid.push((who.clone(), "Vesting schedules may not lock more than MaxTotalVested"));
+		}
+	}
+
+	if invalid.is_empty() { Ok(()) } else { Err(invalid) }
+}
+
+impl<T: Config> Pallet<T> {
+	/// The vesting schedule of `who`, if any.
+	pub fn vesting<K: codec::EncodeLike<T::AccountId>>(
+		who: K,
+	) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		Vesting::<T>::get(who).map(VersionedVestingInfo::into_latest)
+	}
+
+	/// The block at which the vesting schedule of `who` was created, if it has one which was
+	/// created since this has been recorded.
+	pub fn schedule_created_at(who: &T::AccountId) -> Option<T::BlockNumber> {
+		Vesting::<T>::get(who).and_then(VersionedVestingInfo::created_at)
+	}
+
+	/// The amount locked under `vesting` at block `n`, as of the reading of the vesting clock
+	/// then.
+	fn locked_under(
+		vesting: &VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		n: T::BlockNumber,
+	) -> BalanceOf<T> {
+		vesting.locked_at(Self::clock_at(n))
+	}
+
+	/// The rate at which the vesting clock runs: the unlock multiplier, or zero while progression
+	/// is frozen.
+	fn clock_rate() -> FixedU128 {
+		if Self::progression_frozen_since().is_some() {
+			FixedU128::zero()
+		} else {
+			Self::unlock_multiplier()
+		}
+	}
+
+	/// The exact reading of the vesting clock at block `n`, which must not be before the block at
+	/// which it last changed rate.
+	fn clock_reading(n: T::BlockNumber) -> FixedU128 {
+		let (at, reading) = Self::vesting_clock();
+		let elapsed: u128 = n.saturating_sub(at).unique_saturated_into();
+		reading.saturating_add(FixedU128::saturating_from_integer(elapsed).saturating_mul(
+			Self::clock_rate(),
+		))
+	}
+
+	/// The reading of the vesting clock at block `n`, in whole blocks.
+	///
+	/// Only the reading since the clock last changed rate is known; it is taken to have run at a
+	/// rate of one before then.
+	pub(crate) fn clock_at(n: T::BlockNumber) -> T::BlockNumber {
+		let (at, reading) = Self::vesting_clock();
+		if n >= at {
+			Self::whole_blocks(Self::clock_reading(n))
+		} else {
+			Self::whole_blocks(reading).saturating_sub(at - n)
+		}
+	}
+
+	/// The first block at which the vesting clock reads at least `reading`, or `None` if it stands
+	/// still before then.
+	fn block_reaching(reading: T::BlockNumber) -> Option<T::BlockNumber> {
+		let (at, counted) = Self::vesting_clock();
+		let counted_blocks = Self::whole_blocks(counted);
+		if reading <= counted_blocks {
+			return Some(at.saturating_sub(counted_blocks - reading))
+		}
+		let rate = Self::clock_rate();
+		if rate.is_zero() {
+			return None
+		}
+		let target: u128 = reading.unique_saturated_into();
+		let reached = |blocks: u128| {
+			counted.saturating_add(FixedU128::saturating_from_integer(blocks).saturating_mul(rate))
+				.saturating_mul_int(1u128) >= target
+		};
+		let mut blocks = FixedU128::saturating_from_integer(target)
+			.saturating_sub(counted)
+			.checked_div(&rate)?
+			.ceil()
+			.saturating_mul_int(1u128);
+		while !reached(blocks) {
+			blocks = blocks.saturating_add(1);
+		}
+		while blocks > 0 && reached(blocks - 1) {
+			blocks -= 1;
+		}
+		Some(at.saturating_add(T::BlockNumber::unique_saturated_from(blocks)))
+	}
+
+	/// `reading` of the vesting clock, rounded down to whole blocks.
+	fn whole_blocks(reading: FixedU128) -> T::BlockNumber {
+		T::BlockNumber::unique_saturated_from(reading.saturating_mul_int(1u128))
+	}
+
+	/// Record the reading of the vesting clock as of the current block, before its rate changes.
+	fn checkpoint_clock() {
+		let now = <frame_system::Pallet<T>>::block_number();
+		VestingClock::<T>::put((now, Self::clock_reading(now)));
+	}
+
+	/// `schedule`, starting at a block number, with its start converted to the reading of the
+	/// vesting clock as it is kept in storage.
+	///
+	/// The clock reads as many blocks from now as the starting block is, so a schedule starting
+	/// now starts vesting straight away, whichever way the clock has drifted from the block
+	/// number.
+	fn on_clock(
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> VestingInfo<BalanceOf<T>, T::BlockNumber> {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let clock = Self::clock_at(now);
+		let starting_block = if schedule.starting_block >= now {
+			clock.saturating_add(schedule.starting_block - now)
+		} else {
+			clock.saturating_sub(now - schedule.starting_block)
+		};
+		VestingInfo { starting_block, ..schedule }
+	}
+
+	/// Place `schedule` on `who`, locking funds they already hold.
+	fn apply_vesting_schedule(
+		who: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		Self::can_add_vesting_schedule(
+			who,
+			schedule.locked,
+			schedule.per_block,
+			schedule.starting_block,
+		)?;
+		ensure!(T::Currency::free_balance(who) >= schedule.locked, Error::<T>::BalanceTooLow);
+		Self::insert_vesting_schedule(who, schedule);
+		Ok(())
+	}
+
+	/// Whether `who` has a schedule which is fully vested as of the current block.
+	fn is_matured(who: &T::AccountId) -> bool {
+		let now = <frame_system::Pallet<T>>::block_number();
+		Self::vesting(who).map_or(false, |v| Self::locked_under(&v, now).is_zero())
+	}
+
+	/// The block at which `who` would be vested if registered with `register_auto_vest` now:
+	/// the end of their schedule at the current unlock multiplier, but no earlier than the next
+	/// block. Returns `None` if `who` has no schedule.
+	pub(crate) fn auto_vest_block(who: &T::AccountId) -> Option<T::BlockNumber> {
+		let vesting = Self::vesting(who)?;
+		let now = <frame_system::Pallet<T>>::block_number();
+		let end = Self::ending_block(&vesting).unwrap_or_else(|| Self::nominal_end(&vesting));
+		Some(end.max(now + One::one()))
+	}
+
+	/// Whether `vesting` has been fully matured for at least `period` blocks.
+	fn matured_for(
+		vesting: &VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		period: T::BlockNumber,
+	) -> bool {
+		let now = <frame_system::Pallet<T>>::block_number();
+		now >= period && Self::locked_under(&vesting, now - period).is_zero()
+	}
+
+	/// Ensure that the funds of `who` which have vested under `vesting` may be unlocked: `who`
+	/// must meet `VestPrecondition`, the `UnlockOracle` must allow it and any terms required of
+	/// `who` must have been accepted.
+	fn ensure_unlock_allowed(
+		who: &T::AccountId,
+		vesting: &VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(T::VestPrecondition::contains(who), Error::<T>::VestPreconditionUnmet);
+		ensure!(T::UnlockOracle::can_unlock(who, vesting), Error::<T>::UnlockConditionUnmet);
+		ensure!(!PendingTerms::<T>::contains_key(who), Error::<T>::TermsNotAccepted);
+		Ok(())
+	}
+
+	/// Complete the matured schedule `vesting` of `who`, who has not vested it, transferring the
+	/// funds unlocked to `destination` if given. Returns the amount transferred.
+	///
+	/// The funds are only unlocked if they could be by `who` vesting.
+	fn complete_abandoned(
+		who: &T::AccountId,
+		vesting: &VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		destination: Option<&T::AccountId>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		Self::ensure_unlock_allowed(who, vesting)?;
+		with_transaction(|| {
+			let res = Self::update_lock(who.clone()).and_then(|unlocked| match destination {
+				Some(destination) => {
+					let amount = unlocked.min(T::Currency::free_balance(who));
+					T::Currency::transfer(who, destination, amount, ExistenceRequirement::AllowDeath)
+						.map(|_| amount)
+				},
+				None => Ok(Zero::zero()),
+			});
+			match res {
+				Ok(_) => TransactionOutcome::Commit(res),
+				Err(_) => TransactionOutcome::Rollback(res),
+			}
+		})
+	}
+
+	/// Pay out whatever `stream` has streamed by now but has not yet been withdrawn, returning
+	/// the amount paid out.
+	///
+	/// Only what is left of the sender's reserve for streams is paid out, should it have been
+	/// slashed.
+	fn pay_out_stream(
+		stream: &mut Stream<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let amount = stream.withdrawable_at(now);
+		if amount.is_zero() {
+			return Ok(amount)
+		}
+		let leftover = T::Currency::repatriate_reserved_named(
+			&T::ReserveId::get(),
+			&stream.sender,
+			&stream.recipient,
+			amount,
+			BalanceStatus::Free,
+		)?;
+		let paid = amount.saturating_sub(leftover);
+		stream.withdrawn = stream.withdrawn.saturating_add(paid);
+		Ok(paid)
+	}
+
+	/// Pay out `due` of the funds of position `index` to `owner`, burning its NFT and removing it
+	/// once fully paid out. Returns the amount paid out.
+	///
+	/// Must be called within a transaction, as it may fail after paying out.
+	fn pay_out_position(
+		index: u32,
+		position: &mut Position<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+		owner: &T::AccountId,
+		due: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let mut paid = Zero::zero();
+		if !due.is_zero() {
+			let leftover = T::Currency::repatriate_reserved_named(
+				&T::ReserveId::get(),
+				&position.funder,
+				owner,
+				due,
+				BalanceStatus::Free,
+			)?;
+			paid = due.saturating_sub(leftover);
+			position.paid_out = position.paid_out.saturating_add(paid);
+		}
+
+		if position.paid_out == position.schedule.locked {
+			T::Positions::burn_from(&T::PositionClass::get(), &index)?;
+			Positions::<T>::remove(index);
+		} else {
+			Positions::<T>::insert(index, position.clone());
+		}
+		Ok(paid)
+	}
+
+	/// Schedule the transfers of a standing order, returning the address of the scheduler task.
+	fn schedule_standing_order(
+		funder: &T::AccountId,
+		index: u32,
+		interval: T::BlockNumber,
+		count: u32,
+	) -> Result<TaskAddressOf<T>, DispatchError> {
+		let call = Call::<T>::execute_standing_order(funder.clone(), index);
+		let first = <frame_system::Pallet<T>>::block_number().saturating_add(interval);
+		T::Scheduler::schedule(
+			DispatchTime::At(first),
+			Some((interval, count)),
+			LOWEST_PRIORITY,
+			frame_system::RawOrigin::Root.into(),
+			call.into(),
+		)
+	}
+
+	/// The payload which `who` must sign to authorise `vest_unsigned` with `nonce` up to block
+	/// `valid_until`.
+	pub fn vest_payload(who: &T::AccountId, nonce: u32, valid_until: T::BlockNumber) -> Vec<u8> {
+		(VESTING_ID, who, nonce, valid_until).encode()
+	}
+
+	/// The derivative account of `owner` with the given `index`, if `Beneficiaries` supports them.
+	pub fn derivative_account_id(owner: &T::AccountId, index: u16) -> Option<T::AccountId> {
+		T::Beneficiaries::derivative_account(owner, index)
+	}
+
+	/// The sovereign account of `location`, if it has one under `Beneficiaries`.
+	pub fn sovereign_account_id(location: &LocationOf<T>) -> Option<T::AccountId> {
+		T::Beneficiaries::sovereign_account(location)
+	}
+
+	/// Whether some of `who`'s funds have vested since their lock was last updated, such that
+	/// `vest` would unlock them.
+	pub fn can_vest(who: &T::AccountId) -> bool {
+		if !T::VestPrecondition::contains(who) || PendingTerms::<T>::contains_key(who) {
+			return false
+		}
+		Self::vesting(who).map_or(false, |v| {
+			if !T::UnlockOracle::can_unlock(who, &v) {
+				return false
+			}
+			let now = <frame_system::Pallet<T>>::block_number();
+			Self::locked_under(&v, now) < Self::lifetime_totals(who).locked()
+		})
+	}
+
+	/// Unlock any vested funds of `who`.
+	///
+	/// Fails with `NotVesting` for accounts which are not vesting, unless `NoOpVestWhenNotVesting`
+	/// is set, in which case they are left untouched and the call is made free of charge, only
+	/// accounting for the single read needed to find that out.
+	pub(crate) fn do_vest(who: T::AccountId) -> DispatchResultWithPostInfo {
+		let vesting = match Self::vesting(&who) {
+			Some(vesting) => vesting,
+			None if T::NoOpVestWhenNotVesting::get() =>
+				return Ok((Some(T::DbWeight::get().reads(1)), Pays::No).into()),
+			None => return Err(Error::<T>::NotVesting.into()),
+		};
+		Self::ensure_unlock_allowed(&who, &vesting)?;
+		Self::pay_bonus(&who);
+		// Read before the lock is updated, which clears it if the schedule completes.
+		let withholding = Self::withholding(&who);
+		let unlocked = Self::update_lock(who.clone())?;
+		if !unlocked.is_zero() {
+			if let Some((destination, rate)) = withholding {
+				Self::withhold(&who, destination, rate, unlocked);
+			}
+			T::OnVestUnlocked::on_vest_unlocked(&who, unlocked);
+		}
+		Ok(().into())
+	}
+
+	/// Route the fraction `rate` of the `unlocked` funds of `who` to `destination`.
+	///
+	/// Nothing is withheld if the transfer fails, e.g. as it would leave `destination` below the
+	/// existential deposit, so that vesting is never held up by it.
+	fn withhold(
+		who: &T::AccountId,
+		destination: T::AccountId,
+		rate: Perbill,
+		unlocked: BalanceOf<T>,
+	) {
+		let withheld = rate * unlocked;
+		if withheld.is_zero() {
+			return
+		}
+		let withheld =
+			T::Currency::transfer(who, &destination, withheld, ExistenceRequirement::KeepAlive)
+				.map_or_else(|_| Zero::zero(), |_| withheld);
+		Self::deposit_event(Event::<T>::VestingWithheld {
+			account: who.clone(),
+			destination,
+			withheld,
+			kept: unlocked.saturating_sub(withheld),
+		});
+	}
+
+	/// Unlock the vested funds of `who` like `do_vest`, but don't pay a fee if this completes the
+	/// schedule.
+	fn do_vest_free_on_completion(who: T::AccountId) -> DispatchResultWithPostInfo {
+		let mut post_info = Self::do_vest(who.clone())?;
+		if !Vesting::<T>::contains_key(&who) {
+			post_info.pays_fee = Pays::No;
+		}
+		Ok(post_info)
+	}
+
+	/// Revoke `who`'s vesting schedule derived from `plan`, transferring its unvested funds to
+	/// `destination`, or as much of them as is left if some were slashed. The schedule is left in
+	/// place if the funds can't be transferred, and the account is reaped if left with less than
+	/// the existential deposit.
+	fn revoke_vesting(plan: u32, who: &T::AccountId, destination: &T::AccountId) {
+		PlanMembers::<T>::remove(plan, who);
+		let now = <frame_system::Pallet<T>>::block_number();
+		let unvested = match Self::vesting(who) {
+			Some(vesting) => Self::locked_under(&vesting, now),
+			None => return,
+		};
+		let recovered = unvested.min(T::Currency::free_balance(who));
+
+		let result = with_transaction(|| {
+			Self::do_remove_vesting_schedule(who);
+			let result = T::Currency::transfer(
+				who,
+				destination,
+				recovered,
+				ExistenceRequirement::AllowDeath,
+			);
+			match result {
+				Ok(()) => TransactionOutcome::Commit(result),
+				Err(_) => TransactionOutcome::Rollback(result),
+			}
+		});
+		match result {
+			Ok(()) => Self::deposit_event(Event::<T>::VestingRevoked {
+				account: who.clone(),
+				amount: recovered,
+			}),
+			Err(_) => {
+				PlanOf::<T>::remove(who);
+				Self::deposit_event(Event::<T>::VestingRevocationFailed { account: who.clone() })
+			},
+		}
+	}
+
+	/// Pay out the bonus accrued by `who`'s locked funds since it was last paid.
+	///
+	/// The bonus accrues on the average of the amounts locked by the schedule when it was last paid
+	/// and now. Schedules created before the bonus was introduced only accrue from the first time
+	/// they are vested. A bonus which cannot be paid out is forfeited.
+	fn pay_bonus(who: &T::AccountId) {
+		let vesting = match Self::vesting(who) {
+			Some(vesting) => vesting,
+			None => return,
+		};
+		let now = <frame_system::Pallet<T>>::block_number();
+		let rate = T::BonusRate::get();
+		let since = match BonusPaidUntil::<T>::mutate(who, |until| until.replace(now)) {
+			Some(since) if !rate.is_zero() => since,
+			_ => return,
+		};
+
+		let elapsed: BalanceOf<T> = block_number_to_balance(now.saturating_sub(since));
+		let average_locked = Self::locked_under(&vesting, since)
+			.saturating_add(Self::locked_under(&vesting, now)) / 2u32.into();
+		let bonus = rate * average_locked.saturating_mul(elapsed);
+		if !bonus.is_zero() && T::BonusPayout::pay(who, bonus).is_ok() {
+			Self::deposit_event(Event::<T>::BonusPaid { account: who.clone(), bonus });
+		}
+	}
+
+	/// Get the amount of `who`'s current free balance which will still be locked by vesting at
+	/// block `at`.
+	///
+	/// Returns `None` if the account has no vesting schedule.
+	pub fn vesting_balance_at(who: &T::AccountId, at: T::BlockNumber) -> Option<BalanceOf<T>> {
+		Self::vesting(who).map(|v| {
+			let locked_at = Self::locked_under(&v, at);
+			T::Currency::free_balance(who).min(locked_at)
+		})
+	}
+
+	/// Get the amount of `who`'s current free balance which will not be locked by vesting at block
+	/// `at`, assuming the account is vested by then.
+	///
+	/// Other locks on the account are not taken into account.
+	pub fn unlockable_at(who: &T::AccountId, at: T::BlockNumber) -> BalanceOf<T> {
+		let locked = Self::vesting_balance_at(who, at).unwrap_or_else(Zero::zero);
+		T::Currency::free_balance(who).saturating_sub(locked)
+	}
+
+	/// Get the next block after the current one at which the amount locked by `who`'s vesting
+	/// schedule decreases, and the amount by which it does.
+	///
+	/// Returns `None` if the account has no vesting schedule or will not vest any further.
+	pub fn next_unlock_block(who: &T::AccountId) -> Option<(T::BlockNumber, BalanceOf<T>)> {
+		let vesting = Self::vesting(who)?;
+		let now = <frame_system::Pallet<T>>::block_number();
+		let locked_now = Self::locked_under(&vesting, now);
+		if locked_now.is_zero() {
+			return None
+		}
+
+		let reading = Self::clock_at(now).max(vesting.starting_block).saturating_add(One::one());
+		let at = Self::block_reaching(reading)?;
+		Some((at, locked_now.saturating_sub(Self::locked_under(&vesting, at))))
+	}
+
+	/// Get the breakpoints of the amount locked by `who`'s vesting schedule from the current block
+	/// until block `until`, between which it decreases linearly (up to rounding).
+	///
+	/// There are at most three: the current block, the start of the schedule if it is yet to
+	/// start, and the block at which it is fully vested or else `until`.
+	pub fn vesting_timeline(
+		who: &T::AccountId,
+		until: T::BlockNumber,
+	) -> Vec<(T::BlockNumber, BalanceOf<T>)> {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let vesting = match Self::vesting(who) {
+			Some(vesting) if until >= now => vesting,
+			_ => return Vec::new(),
+		};
+		let locked_now = Self::locked_under(&vesting, now);
+		let mut timeline = vec![(now, locked_now)];
+		if locked_now.is_zero() {
+			return timeline
+		}
+
+		if vesting.starting_block > Self::clock_at(now) {
+			match Self::block_reaching(vesting.starting_block) {
+				Some(start) if start < until => timeline.push((start, locked_now)),
+				_ => (),
+			}
+		}
+		match Self::ending_block(&vesting) {
+			Some(end) if end <= until => timeline.push((end, Zero::zero())),
+			_ if until > now => timeline.push((until, Self::locked_under(&vesting, until))),
+			_ => (),
+		}
+		timeline
+	}
+
+	/// Get the status of `who`'s vesting schedule as of the current block.
+	///
+	/// Returns `None` if the account has no vesting schedule.
+	pub fn schedule_status(
+		who: &T::AccountId,
+	) -> Option<ScheduleStatus<BalanceOf<T>, T::BlockNumber>> {
+		let schedule = Self::vesting(who)?;
+		let now = <frame_system::Pallet<T>>::block_number();
+		let remaining = Self::locked_under(&schedule, now);
+		let plan = PlanOf::<T>::get(who);
+		let revocable = plan
+			.and_then(VestingPlans::<T>::get)
+			.map_or(false, |template| template.revocable);
+		Some(ScheduleStatus {
+			schedule,
+			ending_block: Self::ending_block(&schedule),
+			vested: schedule.locked.saturating_sub(remaining),
+			remaining,
+			plan,
+			revocable,
+			created_at: Self::schedule_created_at(who),
+		})
+	}
+
+	/// Get the vesting schedules funded by `source` by vested transfer which are still in place,
+	/// along with their accounts.
+	pub fn funded_schedules(
+		source: &T::AccountId,
+	) -> Vec<(T::AccountId, VestingInfo<BalanceOf<T>, T::BlockNumber>)> {
+		FundedSchedules::<T>::iter_key_prefix(source)
+			.filter_map(|who| Self::vesting(&who).map(|schedule| (who, schedule)))
+			.collect()
+	}
+
+	/// The leaf of the claims Merkle tree allocating `schedule` to `who`.
+	pub fn claim_leaf(
+		who: &T::AccountId,
+		schedule: &VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> T::Hash {
+		T::Hashing::hash_of(&(who, schedule))
+	}
+
+	/// The root of the claims Merkle tree containing `leaf`, given the siblings on its path from
+	/// the bottom up.
+	pub fn claim_root_of(leaf: T::Hash, proof: &[T::Hash]) -> T::Hash {
+		proof.iter().fold(leaf, |node, sibling| {
+			if node <= *sibling {
+				T::Hashing::hash_of(&(node, sibling))
+			} else {
+				T::Hashing::hash_of(&(sibling, node))
+			}
+		})
+	}
 
-		/// Convert the block number into a balance.
-		type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
+	/// Get the number of vesting schedules `who` can currently receive by vested transfer.
+	///
+	/// Accounts only hold one schedule, so this is zero while `who` is vesting or has received
+	/// `MaxIncomingSchedules` in the current period, and one otherwise.
+	pub fn remaining_schedule_slots(who: &T::AccountId) -> u32 {
+		if Vesting::<T>::contains_key(who) || Self::next_incoming_schedules(who).is_err() {
+			0
+		} else {
+			1
+		}
+	}
 
-		/// The minimum amount transferred to call `vested_transfer`.
-		#[pallet::constant]
-		type MinVestedTransfer: Get<BalanceOf<Self>>;
+	/// Check whether a vested transfer of `schedule` to `target` would be accepted, short of the
+	/// sender's balance covering it and the deposit.
+	pub fn validate_vested_transfer(
+		target: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		Self::check_vested_transfer(target, &schedule, false).map(|_| ())
+	}
 
-		/// Weight information for extrinsics in this pallet.
-		type WeightInfo: WeightInfo;
+	/// Get the amount still locked by `who`'s vesting and the block by which it is fully vested, for
+	/// display as a single virtual schedule.
+	///
+	/// As accounts only have one schedule this is simply that schedule's. Returns `None` if the
+	/// account has no vesting schedule or it will never be fully vested.
+	pub fn aggregate_schedule(who: &T::AccountId) -> Option<(BalanceOf<T>, T::BlockNumber)> {
+		let schedule = Self::vesting(who)?;
+		let now = <frame_system::Pallet<T>>::block_number();
+		Some((Self::locked_under(&schedule, now), Self::ending_block(&schedule)?))
 	}
 
-	/// Information regarding the vesting of a given account.
-	#[pallet::storage]
-	#[pallet::getter(fn vesting)]
-	pub type Vesting<T: Config> = StorageMap<
-		_,
-		Blake2_128Concat,
-		T::AccountId,
-		VestingInfo<BalanceOf<T>, T::BlockNumber>,
-	>;
+	/// The reading of the vesting clock at which `vesting` is fully vested, under which it is kept
+	/// in `SchedulesEndingAt`.
+	pub(crate) fn nominal_end(vesting: &VestingInfo<BalanceOf<T>, T::BlockNumber>) -> T::BlockNumber {
+		vesting.ending_block()
+	}
 
-	#[pallet::pallet]
-	#[pallet::generate_store(pub(super) trait Store)]
-	pub struct Pallet<T>(_);
+	/// The number of blocks which must count toward `vesting` for it to be fully vested.
+	fn blocks_to_vest(vesting: &VestingInfo<BalanceOf<T>, T::BlockNumber>) -> u128 {
+		let per_block = vesting.per_block.max(One::one());
+		(vesting.locked.saturating_add(per_block - One::one()) / per_block).unique_saturated_into()
+	}
 
-	#[pallet::genesis_config]
-	pub struct GenesisConfig<T: Config> {
-		pub vesting: Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>,
+	/// The block at which `vesting` is fully vested at the current rate of the vesting clock, or
+	/// `None` if it never will be.
+	fn ending_block(vesting: &VestingInfo<BalanceOf<T>, T::BlockNumber>) -> Option<T::BlockNumber> {
+		let blocks = T::BlockNumber::unique_saturated_from(Self::blocks_to_vest(vesting));
+		Self::block_reaching(vesting.starting_block.saturating_add(blocks))
 	}
 
-	#[cfg(feature = "std")]
-	impl<T: Config> Default for GenesisConfig<T> {
-		fn default() -> Self {
-			GenesisConfig {
-				vesting: Default::default(),
-			}
-		}
+	/// Get a summary of `who`'s vesting as of the current block.
+	///
+	/// Returns `None` if the account has no vesting schedule.
+	pub fn vesting_summary(who: &T::AccountId) -> Option<VestingSummary<BalanceOf<T>, T::BlockNumber>> {
+		let schedule = Self::vesting(who)?;
+		let now = <frame_system::Pallet<T>>::block_number();
+		let locked_now = Self::locked_under(&schedule, now);
+		Some(VestingSummary {
+			schedule,
+			unvested: T::Currency::free_balance(who).min(locked_now),
+			vestable: Self::lifetime_totals(who).locked().saturating_sub(locked_now),
+		})
 	}
 
-	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
-		fn build(&self) {
-			use sp_runtime::traits::Saturating;
+	/// Get the vesting summary of each of `accounts`, in the same order.
+	pub fn vesting_summaries(
+		accounts: &[T::AccountId],
+	) -> Vec<Option<VestingSummary<BalanceOf<T>, T::BlockNumber>>> {
+		accounts.iter().map(Self::vesting_summary).collect()
+	}
 
-			// Generate initial vesting configuration
-			// * who - Account which we are generating vesting configuration for
-			// * begin - Block when the account will start to vest
-			// * length - Number of blocks from `begin` until fully vested
-			// * liquid - Number of units which can be spent before vesting begins
-			for &(ref who, begin, length, liquid) in self.vesting.iter() {
-				let balance = T::Currency::free_balance(who);
-				assert!(!balance.is_zero(), "Currencies must be init'd before vesting");
-				// Total genesis `balance` minus `liquid` equals funds locked for vesting
-				let locked = balance.saturating_sub(liquid);
-				let length_as_balance = T::BlockNumberToBalance::convert(length);
-				let per_block = locked / length_as_balance.max(sp_runtime::traits::One::one());
+	/// Get the summaries of up to `limit` vesting accounts, in storage order, starting after the
+	/// account `start_after` or else from the first.
+	///
+	/// Pages are fetched by passing the last account of the previous page as `start_after`; a
+	/// page shorter than `limit` is the last.
+	pub fn vesting_accounts(
+		start_after: Option<T::AccountId>,
+		limit: u32,
+	) -> Vec<(T::AccountId, VestingSummary<BalanceOf<T>, T::BlockNumber>)> {
+		let prefix = Vesting::<T>::final_prefix();
+		let mut key = match start_after {
+			Some(who) => Vesting::<T>::hashed_key_for(who),
+			None => prefix.to_vec(),
+		};
+		let mut page = Vec::new();
+		while page.len() < limit as usize {
+			key = match frame_support::sp_io::storage::next_key(&key) {
+				Some(next) if next.starts_with(&prefix) => next,
+				_ => break,
+			};
+			let mut raw_who = Blake2_128Concat::reverse(&key[prefix.len()..]);
+			let summary = T::AccountId::decode(&mut raw_who).ok()
+				.and_then(|who| Self::vesting_summary(&who).map(|summary| (who, summary)));
+			page.extend(summary);
+		}
+		page
+	}
 
-				Vesting::<T>::insert(who, VestingInfo {
-					locked: locked,
-					per_block: per_block,
-					starting_block: begin
-				});
-				let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
-				T::Currency::set_lock(VESTING_ID, who, locked, reasons);
+	/// Get the total amount which is still unvested across up to `limit` vesting accounts, in
+	/// storage order, starting after the account `start_after` or else from the first.
+	///
+	/// Also returns the last account counted, to be passed as `start_after` for the next page, or
+	/// `None` once every account has been counted. The total across all accounts is the sum of
+	/// the pages, all fetched at the same block.
+	pub fn total_unvested(
+		start_after: Option<T::AccountId>,
+		limit: u32,
+	) -> (BalanceOf<T>, Option<T::AccountId>) {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let prefix = Vesting::<T>::final_prefix();
+		let mut key = match start_after {
+			Some(who) => Vesting::<T>::hashed_key_for(who),
+			None => prefix.to_vec(),
+		};
+		let mut total: BalanceOf<T> = Zero::zero();
+		let mut last = None;
+		for _ in 0..limit {
+			key = match frame_support::sp_io::storage::next_key(&key) {
+				Some(next) if next.starts_with(&prefix) => next,
+				_ => return (total, None),
+			};
+			let mut raw_who = Blake2_128Concat::reverse(&key[prefix.len()..]);
+			if let Ok(who) = T::AccountId::decode(&mut raw_who) {
+				let unvested = Self::vesting_balance_at(&who, now).unwrap_or_else(Zero::zero);
+				total = total.saturating_add(unvested);
+				last = Some(who);
 			}
 		}
+		(total, last)
 	}
 
-	#[pallet::event]
-	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
-	pub enum Event<T: Config> {
-		/// The amount vested has been updated. This could indicate more funds are available. The
-		/// balance given is the amount which is left unvested (and thus locked).
-		/// \[account, unvested\]
-		VestingUpdated(T::AccountId, BalanceOf<T>),
-		/// An \[account\] has become fully vested. No further vesting can happen.
-		VestingCompleted(T::AccountId),
+	/// The amount of `who`'s unvested balance which may be bonded for staking.
+	///
+	/// The vesting lock only prevents transfers and reserves, and locks overlap rather than stack,
+	/// so all unvested funds may also be bonded. Funds which vest while bonded are only unlocked by
+	/// `vest` once unbonded, and a bond may outlast the vesting schedule.
+	pub fn stakeable_unvested(who: &T::AccountId) -> BalanceOf<T> {
+		let now = <frame_system::Pallet<T>>::block_number();
+		Self::vesting_balance_at(who, now).unwrap_or_else(Zero::zero)
 	}
 
-	/// Error for the vesting pallet.
-	#[pallet::error]
-	pub enum Error<T> {
-		/// The account given is not vesting.
-		NotVesting,
-		/// An existing vesting schedule already exists for this account that cannot be clobbered.
-		ExistingVestingSchedule,
-		/// Amount being transferred is too low to create a vesting schedule.
-		AmountLow,
+	/// Reduce `who`'s vesting schedule after `amount` of their funds has been slashed.
+	///
+	/// From the current block on the schedule locks up to `amount` less, with the amount unlocked
+	/// per block scaled down proportionally so that it still ends at about the same block. The
+	/// reduction is accounted for as revoked.
+	///
+	/// Returns the amount by which the locked balance was reduced.
+	pub fn on_slash(who: &T::AccountId, amount: BalanceOf<T>) -> BalanceOf<T> {
+		let vesting = match Self::vesting(who) {
+			Some(vesting) => vesting,
+			None => return Zero::zero(),
+		};
+		let now = <frame_system::Pallet<T>>::block_number();
+		let locked_now = Self::locked_under(&vesting, now);
+		let reduction = amount.min(locked_now);
+		if reduction.is_zero() {
+			return Zero::zero()
+		}
+
+		let remaining = locked_now.saturating_sub(reduction);
+		let ratio = Perbill::from_rational(remaining, locked_now);
+		let reduced = VestingInfo {
+			locked: remaining,
+			per_block: (ratio * vesting.per_block).max(One::one()),
+			starting_block: vesting.starting_block.max(Self::clock_at(now)),
+		};
+		Vesting::<T>::mutate(who, |stored| *stored = stored.map(|s| s.with_schedule(reduced)));
+		SchedulesEndingAt::<T>::remove(Self::nominal_end(&vesting), who);
+		SchedulesEndingAt::<T>::insert(Self::nominal_end(&reduced), who, ());
+		LifetimeTotals::<T>::mutate(who, |totals| {
+			totals.revoked = totals.revoked.saturating_add(reduction)
+		});
+		// it can't fail, but even if somehow it did, we don't really care.
+		let res = Self::update_lock(who.clone());
+		debug_assert!(res.is_ok());
+		reduction
 	}
 
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
-		/// Unlock any vested funds of the sender account.
-		///
-		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
-		/// locked under this pallet.
-		///
-		/// Emits either `VestingCompleted` or `VestingUpdated`.
-		///
-		/// # <weight>
-		/// - `O(1)`.
-		/// - DbWeight: 2 Reads, 2 Writes
-		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
-		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
-		/// # </weight>
-		#[pallet::weight(T::WeightInfo::vest_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get()))
-		)]
-		pub fn vest(origin: OriginFor<T>) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			Self::update_lock(who)
+	/// Remove `who`'s vesting schedule and lock, accounting for whatever was still locked as
+	/// revoked.
+	fn do_remove_vesting_schedule(who: &T::AccountId) {
+		if let Some(vesting) = Vesting::<T>::take(who).map(VersionedVestingInfo::into_latest) {
+			SchedulesEndingAt::<T>::remove(Self::nominal_end(&vesting), who);
+			let now = <frame_system::Pallet<T>>::block_number();
+			let locked_now = Self::locked_under(&vesting, now);
+			LifetimeTotals::<T>::mutate(who, |totals| totals.revoke(locked_now));
+			Self::clear_schedule_data(who);
+			T::Currency::remove_lock(VESTING_ID, who);
+			T::OnLockedChanged::on_locked_changed(who, Zero::zero());
 		}
+	}
 
-		/// Unlock any vested funds of a `target` account.
-		///
-		/// The dispatch origin for this call must be _Signed_.
-		///
-		/// - `target`: The account whose vested funds should be unlocked. Must have funds still
-		/// locked under this pallet.
-		///
-		/// Emits either `VestingCompleted` or `VestingUpdated`.
-		///
-		/// # <weight>
-		/// - `O(1)`.
-		/// - DbWeight: 3 Reads, 3 Writes
-		///     - Reads: Vesting Storage, Balances Locks, Target Account
-		///     - Writes: Vesting Storage, Balances Locks, Target Account
-		/// # </weight>
-		#[pallet::weight(T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
-		)]
-		pub fn vest_other(origin: OriginFor<T>, target: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
-			ensure_signed(origin)?;
-			Self::update_lock(T::Lookup::lookup(target)?)
+	/// Remove the data kept alongside `who`'s vesting schedule once it has ended.
+	fn clear_schedule_data(who: &T::AccountId) {
+		BonusPaidUntil::<T>::remove(who);
+		ContingentBeneficiary::<T>::remove(who);
+		Withholding::<T>::remove(who);
+		ScheduleMetadata::<T>::remove(who);
+		if let Some((depositor, deposit)) = ScheduleDeposits::<T>::take(who) {
+			T::Currency::unreserve_named(&T::ReserveId::get(), &depositor, deposit);
+		}
+		if let Some(plan) = PlanOf::<T>::take(who) {
+			PlanMembers::<T>::remove(plan, who);
+		}
+		if let Some(source) = FundedBy::<T>::take(who) {
+			FundedSchedules::<T>::remove(source, who);
+		}
+		SelfImposed::<T>::remove(who);
+		if let Some(at) = AutoVestAt::<T>::take(who) {
+			AutoVestQueue::<T>::mutate(at, |queue| queue.retain(|queued| queued != who));
 		}
+	}
 
-		/// Create a vested transfer.
-		///
-		/// The dispatch origin for this call must be _Signed_.
-		///
-		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
-		/// - `schedule`: The vesting schedule attached to the transfer.
-		///
-		/// Emits `VestingCreated`.
-		///
-		/// # <weight>
-		/// - `O(1)`.
-		/// - DbWeight: 3 Reads, 3 Writes
-		///     - Reads: Vesting Storage, Balances Locks, Target Account, [Sender Account]
-		///     - Writes: Vesting Storage, Balances Locks, Target Account, [Sender Account]
-		/// # </weight>
-		#[pallet::weight(T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get()))]
-		pub fn vested_transfer(
-			origin: OriginFor<T>,
-			target: <T::Lookup as StaticLookup>::Source,
-			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
-		) -> DispatchResult {
-			let transactor = ensure_signed(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+	/// Place `schedule` on `who` at genesis and lock the funds it covers.
+	#[cfg(feature = "std")]
+	fn insert_genesis_schedule(
+		who: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		endow: bool,
+	) -> Result<(), &'static str> {
+		if Vesting::<T>::contains_key(who) {
+			return Err("Accounts may only have one vesting schedule")
+		}
+		if T::MaxTotalVested::get().map_or(false, |max| schedule.locked > max) {
+			return Err("Vesting schedules may not lock more than MaxTotalVested")
+		}
+		if endow {
+			let _ = T::Currency::deposit_creating(who, schedule.locked);
+		}
+		if T::Currency::free_balance(who) < schedule.locked {
+			return Err("Currencies must be init'd before vesting")
+		}
+		let now = <frame_system::Pallet<T>>::block_number();
+		Vesting::<T>::insert(who, VersionedVestingInfo::new(schedule, now));
+		SchedulesEndingAt::<T>::insert(Self::nominal_end(&schedule), who, ());
+		LifetimeTotals::<T>::mutate(who, |totals| totals.receive(schedule.locked));
+		BonusPaidUntil::<T>::insert(who, T::BlockNumber::zero());
+		let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
+		T::Currency::set_lock(VESTING_ID, who, schedule.locked, reasons);
+		T::OnLockedChanged::on_locked_changed(who, schedule.locked);
+		Ok(())
+	}
 
-			let who = T::Lookup::lookup(target)?;
-			ensure!(!Vesting::<T>::contains_key(&who), Error::<T>::ExistingVestingSchedule);
+	/// Ensure `schedule` locks enough for a vested transfer according to the
+	/// `MinVestedTransferPolicy`.
+	fn ensure_min_vested_transfer(
+		schedule: &VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		forced: bool,
+	) -> DispatchResult {
+		let policy = T::MinVestedTransferPolicy::get();
+		if forced && policy.exempt_forced {
+			return Ok(())
+		}
+		let locked = if policy.check_remaining {
+			Self::locked_under(schedule, <frame_system::Pallet<T>>::block_number())
+		} else {
+			schedule.locked
+		};
+		let min = Self::min_vested_transfer();
+		let enough = if policy.inclusive { locked >= min } else { locked > min };
+		ensure!(enough, Error::<T>::AmountLow);
+		Ok(())
+	}
 
-			T::Currency::transfer(&transactor, &who, schedule.locked, ExistenceRequirement::AllowDeath)?;
+	/// Check that `schedule` may be placed on `target` by vested transfer, returning the count of
+	/// schedules `target` will have received in the current period, if limited.
+	fn check_vested_transfer(
+		target: &T::AccountId,
+		schedule: &VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		forced: bool,
+	) -> Result<Option<(T::BlockNumber, u32)>, DispatchError> {
+		ensure!(!Self::paused(), Error::<T>::Paused);
+		Self::ensure_min_vested_transfer(schedule, forced)?;
+		Self::can_add_vesting_schedule(
+			target,
+			schedule.locked,
+			schedule.per_block,
+			schedule.starting_block,
+		)?;
+		Self::next_incoming_schedules(target)
+	}
 
-			Self::add_vesting_schedule(&who, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
+	/// Transfer `schedule.locked` from `source` to `target` and place it under `schedule`,
+	/// `forced` being whether the transfer is made by `ForceOrigin`.
+	fn do_vested_transfer(
+		source: &T::AccountId,
+		target: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		forced: bool,
+	) -> DispatchResult {
+		let incoming = Self::check_vested_transfer(target, &schedule, forced)?;
 
-			Ok(())
+		let id = T::ReserveId::get();
+		let deposit = T::ScheduleDeposit::get();
+		T::Currency::reserve_named(&id, source, deposit)?;
+		let transfer =
+			T::Currency::transfer(source, target, schedule.locked, ExistenceRequirement::AllowDeath);
+		if transfer.is_err() {
+			T::Currency::unreserve_named(&id, source, deposit);
+			return transfer
 		}
 
-		/// Force a vested transfer.
-		///
-		/// The dispatch origin for this call must be _Root_.
-		///
-		/// - `source`: The account whose funds should be transferred.
-		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
-		/// - `schedule`: The vesting schedule attached to the transfer.
-		///
-		/// Emits `VestingCreated`.
-		///
-		/// # <weight>
-		/// - `O(1)`.
-		/// - DbWeight: 4 Reads, 4 Writes
-		///     - Reads: Vesting Storage, Balances Locks, Target Account, Source Account
-		///     - Writes: Vesting Storage, Balances Locks, Target Account, Source Account
-		/// # </weight>
-		#[pallet::weight(T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get()))]
-		pub fn force_vested_transfer(
-			origin: OriginFor<T>,
-			source: <T::Lookup as StaticLookup>::Source,
-			target: <T::Lookup as StaticLookup>::Source,
-			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
-		) -> DispatchResult {
-			ensure_root(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+		if let Some(incoming) = incoming {
+			IncomingSchedules::<T>::insert(target, incoming);
+		}
+		if !deposit.is_zero() {
+			ScheduleDeposits::<T>::insert(target, (source.clone(), deposit));
+		}
+		FundedBy::<T>::insert(target, source);
+		FundedSchedules::<T>::insert(source, target, ());
+		Self::insert_vesting_schedule(target, schedule);
 
-			let target = T::Lookup::lookup(target)?;
-			let source = T::Lookup::lookup(source)?;
-			ensure!(!Vesting::<T>::contains_key(&target), Error::<T>::ExistingVestingSchedule);
+		Ok(())
+	}
 
-			T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+	/// The record of vested transfers received by `who` in the current period once it has
+	/// received another one, or `None` if they are not limited.
+	fn next_incoming_schedules(
+		who: &T::AccountId,
+	) -> Result<Option<(T::BlockNumber, u32)>, DispatchError> {
+		let period = T::IncomingSchedulePeriod::get();
+		if period.is_zero() {
+			return Ok(None)
+		}
+		let now = <frame_system::Pallet<T>>::block_number();
+		let current = now - now % period;
+		let received = match Self::incoming_schedules(who) {
+			Some((start, received)) if start == current => received,
+			_ => 0,
+		};
+		ensure!(received < T::MaxIncomingSchedules::get(), Error::<T>::TooManyIncomingSchedules);
+		Ok(Some((current, received + 1)))
+	}
 
-			Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
+	/// Place `schedule` on `who` and lock the funds it covers.
+	///
+	/// The caller is responsible for checking `can_add_vesting_schedule` beforehand.
+	fn insert_vesting_schedule(
+		who: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) {
+		let schedule = Self::on_clock(schedule);
+		let now = <frame_system::Pallet<T>>::block_number();
+		Vesting::<T>::insert(who, VersionedVestingInfo::new(schedule, now));
+		SchedulesEndingAt::<T>::insert(Self::nominal_end(&schedule), who, ());
+		LifetimeTotals::<T>::mutate(who, |totals| totals.receive(schedule.locked));
+		BonusPaidUntil::<T>::insert(who, now);
+		// it can't fail, but even if somehow it did, we don't really care.
+		let res = Self::update_lock(who.clone());
+		debug_assert!(res.is_ok());
+	}
 
-			Ok(())
-		}
+	/// Keep `schedule`, which completed at `now`, in `who`'s `CompletedVesting`, deleting their
+	/// oldest completed schedule if they already have `MaxArchived` of them.
+	fn archive(
+		who: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		now: T::BlockNumber,
+	) {
+		CompletedVesting::<T>::mutate(who, |archived| {
+			if archived.len() >= T::MaxArchived::get() as usize && !archived.is_empty() {
+				archived.remove(0);
+			}
+			// Only fails if `MaxArchived` is zero, in which case nothing is kept.
+			let _ = archived.try_push((schedule, now));
+		});
 	}
-}
 
-impl<T: Config> Pallet<T> {
 	/// (Re)set or remove the pallet's currency lock on `who`'s account in accordance with their
 	/// current unvested amount.
-	fn update_lock(who: T::AccountId) -> DispatchResult {
+	///
+	/// Returns the amount which has been unlocked since the lock was last updated.
+	fn update_lock(who: T::AccountId) -> Result<BalanceOf<T>, DispatchError> {
 		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
 		let now = <frame_system::Pallet<T>>::block_number();
-		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		let locked_now = Self::locked_under(&vesting, now);
+		let unlocked = LifetimeTotals::<T>::mutate(&who, |totals| totals.vest(locked_now));
 
+		T::OnLockedChanged::on_locked_changed(&who, locked_now);
 		if locked_now.is_zero() {
 			T::Currency::remove_lock(VESTING_ID, &who);
 			Vesting::<T>::remove(&who);
-			Self::deposit_event(Event::<T>::VestingCompleted(who));
+			SchedulesEndingAt::<T>::remove(Self::nominal_end(&vesting), &who);
+			Self::clear_schedule_data(&who);
+			if T::ArchiveCompletedSchedules::get() {
+				Self::archive(&who, vesting, now);
+			}
+			T::OnVestingCompleted::on_vesting_completed(&who);
+			Self::deposit_event(Event::<T>::VestingCompleted { account: who });
 		} else {
 			let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
 			T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
-			Self::deposit_event(Event::<T>::VestingUpdated(who, locked_now));
+			Self::deposit_event(Event::<T>::VestingUpdated { account: who, unvested: locked_now });
 		}
-		Ok(())
+		Ok(unlocked)
 	}
 }
 
@@ -365,13 +4271,7 @@ impl<T: Config> VestingSchedule<T::AccountId> for Pallet<T> where
 
 	/// Get the amount that is currently being vested and cannot be transferred out of this account.
 	fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T>> {
-		if let Some(v) = Self::vesting(who) {
-			let now = <frame_system::Pallet<T>>::block_number();
-			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
-			Some(T::Currency::free_balance(who).min(locked_now))
-		} else {
-			None
-		}
+		Self::vesting_balance_at(who, <frame_system::Pallet<T>>::block_number())
 	}
 
 	/// Adds a vesting schedule to a given account.
@@ -384,6 +4284,8 @@ impl<T: Config> VestingSchedule<T::AccountId> for Pallet<T> where
 	/// `vest_other`.
 	///
 	/// Is a no-op if the amount to be vested is zero.
+	///
+	/// Callers should account for `WeightInfo::add_vesting_schedule` in their own weight.
 	fn add_vesting_schedule(
 		who: &T::AccountId,
 		locked: BalanceOf<T>,
@@ -391,26 +4293,89 @@ impl<T: Config> VestingSchedule<T::AccountId> for Pallet<T> where
 		starting_block: T::BlockNumber
 	) -> DispatchResult {
 		if locked.is_zero() { return Ok(()) }
-		if Vesting::<T>::contains_key(who) {
-			Err(Error::<T>::ExistingVestingSchedule)?
+		Self::can_add_vesting_schedule(who, locked, per_block, starting_block)?;
+		Self::insert_vesting_schedule(who, VestingInfo { locked, per_block, starting_block });
+		Ok(())
+	}
+
+	/// Checks if `add_vesting_schedule` would work against `who`.
+	///
+	/// Fails if `who` already has a vesting schedule or if `locked` or `per_block` are zero.
+	fn can_add_vesting_schedule(
+		who: &T::AccountId,
+		locked: BalanceOf<T>,
+		per_block: BalanceOf<T>,
+		_starting_block: T::BlockNumber,
+	) -> DispatchResult {
+		ensure!(!locked.is_zero() && !per_block.is_zero(), Error::<T>::InvalidScheduleParams);
+		ensure!(!Vesting::<T>::contains_key(who), Error::<T>::ExistingVestingSchedule);
+		if let Some(max) = T::MaxTotalVested::get() {
+			ensure!(locked <= max, Error::<T>::TooMuchVested);
 		}
-		let vesting_schedule = VestingInfo {
-			locked,
-			per_block,
-			starting_block
-		};
-		Vesting::<T>::insert(who, vesting_schedule);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let res = Self::update_lock(who.clone());
-		debug_assert!(res.is_ok());
 		Ok(())
 	}
 
 	/// Remove a vesting schedule for a given account.
+	///
+	/// Whatever was still locked under the schedule is accounted for as revoked.
+	///
+	/// Callers should account for `WeightInfo::remove_vesting_schedule` in their own weight.
 	fn remove_vesting_schedule(who: &T::AccountId) {
-		Vesting::<T>::remove(who);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let res = Self::update_lock(who.clone());
-		debug_assert!(res.is_ok());
+		Self::do_remove_vesting_schedule(who)
+	}
+}
+
+impl<T: Config> OnKilledAccount<T::AccountId> for Pallet<T> {
+	/// Clean up the vesting schedule and any pending terms of a reaped account.
+	fn on_killed_account(who: &T::AccountId) {
+		PendingTerms::<T>::remove(who);
+		IncomingSchedules::<T>::remove(who);
+		Self::do_remove_vesting_schedule(who)
+	}
+}
+
+impl<T: Config> VestedTransfer<T::AccountId> for Pallet<T> where
+	BalanceOf<T>: MaybeSerializeDeserialize + Debug
+{
+	type Moment = T::BlockNumber;
+	type Currency = T::Currency;
+
+	/// Transfer `locked` from `source` to `target` under a new vesting schedule.
+	///
+	/// Subject to the same checks as the `vested_transfer` call: the amount must be at least
+	/// `MinVestedTransfer` and `target` must not already have a vesting schedule.
+	fn vested_transfer(
+		source: &T::AccountId,
+		target: &T::AccountId,
+		locked: BalanceOf<T>,
+		per_block: BalanceOf<T>,
+		starting_block: T::BlockNumber,
+	) -> DispatchResult {
+		let schedule = VestingInfo { locked, per_block, starting_block };
+		Self::do_vested_transfer(source, target, schedule, false)
+	}
+}
+
+impl<T: Config> VestingInspect<T::AccountId> for Pallet<T> {
+	type Balance = BalanceOf<T>;
+	type BlockNumber = T::BlockNumber;
+
+	fn vesting_schedule(who: &T::AccountId) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		Self::vesting(who)
+	}
+
+	fn vesting_balance_at(who: &T::AccountId, at: T::BlockNumber) -> Option<BalanceOf<T>> {
+		Self::vesting_balance_at(who, at)
+	}
+
+	fn stakeable_unvested(who: &T::AccountId) -> BalanceOf<T> {
+		Self::stakeable_unvested(who)
+	}
+}
+
+impl<T: Config> UnvestedBalance<T::AccountId, BalanceOf<T>> for Pallet<T> {
+	/// The part of `who`'s free balance which is still locked by their vesting schedule.
+	fn unvested_balance(who: &T::AccountId) -> BalanceOf<T> {
+		Self::stakeable_unvested(who)
 	}
 }
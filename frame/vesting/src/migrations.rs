@@ -0,0 +1,225 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the vesting pallet.
+
+use super::*;
+
+/// Storage items in the layouts which preceded the current one, for the migrations from them.
+pub mod deprecated {
+	use super::*;
+	use frame_support::{
+		storage::types::StorageMap,
+		traits::{PalletInfo, StorageInstance},
+	};
+
+	/// The prefix of `Vesting`.
+	pub struct VestingPrefix<T>(PhantomData<T>);
+	impl<T: Config> StorageInstance for VestingPrefix<T> {
+		fn pallet_prefix() -> &'static str {
+			<T as frame_system::Config>::PalletInfo::name::<Pallet<T>>()
+				.expect("Vesting is part of the runtime; qed")
+		}
+		const STORAGE_PREFIX: &'static str = "Vesting";
+	}
+
+	/// `Vesting` before `V4`, when schedules were stored unversioned.
+	pub type Vesting<T> = StorageMap<
+		VestingPrefix<T>,
+		Blake2_128Concat,
+		<T as frame_system::Config>::AccountId,
+		VestingInfo<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>,
+	>;
+}
+
+pub mod v1 {
+	use super::*;
+
+	/// Check to execute prior to migration.
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() == Releases::V0, "Vesting storage is not at V0");
+		Ok(())
+	}
+
+	/// Initialise the lifetime totals of every account which was already vesting before they
+	/// were introduced, as if its schedule were its first.
+	///
+	/// The vesting lock of each such account is brought down to what is still locked under its
+	/// schedule, unlocking whatever it had vested but not claimed, so that it matches the totals.
+	pub fn migrate<T: Config>() -> Weight {
+		log::info!(target: "runtime::vesting", "Migrating vesting to Releases::V1");
+		let now = <frame_system::Pallet<T>>::block_number();
+		let mut reads = 1u64;
+		let mut writes = 1u64;
+		for (who, schedule) in deprecated::Vesting::<T>::iter() {
+			reads = reads.saturating_add(2);
+			if LifetimeTotals::<T>::contains_key(&who) {
+				continue
+			}
+			let locked_now = Pallet::<T>::locked_under(&schedule, now);
+			LifetimeTotals::<T>::mutate(&who, |totals| {
+				totals.receive(schedule.locked);
+				totals.vest(locked_now);
+			});
+			if locked_now.is_zero() {
+				T::Currency::remove_lock(VESTING_ID, &who);
+			} else {
+				let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
+				T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
+			}
+			writes = writes.saturating_add(2);
+		}
+
+		StorageVersion::<T>::put(Releases::V1);
+		log::info!(target: "runtime::vesting", "Completed vesting migration to Releases::V1");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+
+	/// Check to execute after migration.
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() >= Releases::V1, "Vesting storage is not at V1");
+		for (who, _) in Vesting::<T>::iter() {
+			ensure!(
+				LifetimeTotals::<T>::get(&who).schedules > 0,
+				"Vesting account has no lifetime totals",
+			);
+		}
+		Ok(())
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Check to execute prior to migration.
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() == Releases::V1, "Vesting storage is not at V1");
+		Ok(())
+	}
+
+	/// Rewrite every legacy schedule which fails `VestingInfo::is_valid`: those which lock
+	/// nothing are completed, and a zero `per_block` is raised as by `VestingInfo::correct`.
+	pub fn migrate<T: Config>() -> Weight {
+		log::info!(target: "runtime::vesting", "Migrating vesting to Releases::V2");
+		let mut reads = 0u64;
+		let mut writes = 1u64;
+		for (who, schedule) in deprecated::Vesting::<T>::iter() {
+			reads = reads.saturating_add(1);
+			if schedule.is_valid() {
+				continue
+			}
+			if schedule.locked.is_zero() {
+				deprecated::Vesting::<T>::remove(&who);
+				LifetimeTotals::<T>::mutate(&who, |totals| totals.vest(Zero::zero()));
+				Pallet::<T>::clear_schedule_data(&who);
+				T::Currency::remove_lock(VESTING_ID, &who);
+				writes = writes.saturating_add(7);
+			} else {
+				deprecated::Vesting::<T>::insert(&who, schedule.correct());
+				writes = writes.saturating_add(1);
+			}
+		}
+
+		StorageVersion::<T>::put(Releases::V2);
+		log::info!(target: "runtime::vesting", "Completed vesting migration to Releases::V2");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+
+	/// Check to execute after migration.
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() >= Releases::V2, "Vesting storage is not at V2");
+		for schedule in Vesting::<T>::iter_values().map(VersionedVestingInfo::into_latest) {
+			ensure!(schedule.is_valid(), "Invalid vesting schedule remains");
+		}
+		Ok(())
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Check to execute prior to migration.
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() == Releases::V2, "Vesting storage is not at V2");
+		Ok(())
+	}
+
+	/// Index every schedule by the block at which it ends in `SchedulesEndingAt`.
+	pub fn migrate<T: Config>() -> Weight {
+		log::info!(target: "runtime::vesting", "Migrating vesting to Releases::V3");
+		let mut schedules = 0u64;
+		for (who, schedule) in deprecated::Vesting::<T>::iter() {
+			SchedulesEndingAt::<T>::insert(Pallet::<T>::nominal_end(&schedule), who, ());
+			schedules = schedules.saturating_add(1);
+		}
+
+		StorageVersion::<T>::put(Releases::V3);
+		log::info!(target: "runtime::vesting", "Completed vesting migration to Releases::V3");
+
+		T::DbWeight::get().reads_writes(schedules, schedules.saturating_add(1))
+	}
+
+	/// Check to execute after migration.
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() >= Releases::V3, "Vesting storage is not at V3");
+		for (who, schedule) in Vesting::<T>::iter() {
+			let schedule = schedule.into_latest();
+			ensure!(
+				SchedulesEndingAt::<T>::contains_key(Pallet::<T>::nominal_end(&schedule), who),
+				"Vesting schedule is not indexed by its end",
+			);
+		}
+		Ok(())
+	}
+}
+
+pub mod v4 {
+	use super::*;
+
+	/// Check to execute prior to migration.
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() == Releases::V3, "Vesting storage is not at V3");
+		Ok(())
+	}
+
+	/// Store every schedule as `VersionedVestingInfo`, so that later layouts can be introduced
+	/// as new versions without migrating every schedule.
+	pub fn migrate<T: Config>() -> Weight {
+		log::info!(target: "runtime::vesting", "Migrating vesting to Releases::V4");
+		let mut schedules = 0u64;
+		Vesting::<T>::translate::<VestingInfo<BalanceOf<T>, T::BlockNumber>, _>(|_, schedule| {
+			schedules = schedules.saturating_add(1);
+			Some(schedule.into())
+		});
+
+		StorageVersion::<T>::put(Releases::V4);
+		log::info!(target: "runtime::vesting", "Completed vesting migration to Releases::V4");
+
+		T::DbWeight::get().reads_writes(schedules, schedules.saturating_add(1))
+	}
+
+	/// Check to execute after migration.
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() == Releases::V4, "Vesting storage is not at V4");
+		for who in Vesting::<T>::iter_keys() {
+			ensure!(Vesting::<T>::get(&who).is_some(), "Vesting schedule is not versioned");
+		}
+		Ok(())
+	}
+}
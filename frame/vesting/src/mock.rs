@@ -15,11 +15,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use frame_support::parameter_types;
+use std::cell::RefCell;
+
+use frame_support::{parameter_types, traits::Contains, weights::Weight};
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, Identity, IdentityLookup},
+	testing::{Header, TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, IdentityLookup},
+	transaction_validity::TransactionPriority,
+	Perbill,
 };
 
 use super::*;
@@ -36,7 +40,10 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>},
+		Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
+		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
+		Uniques: pallet_uniques::{Pallet, Call, Storage, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -61,7 +68,7 @@ impl frame_system::Config for Test {
 	type Header = Header;
 	type Index = u64;
 	type Lookup = IdentityLookup<Self::AccountId>;
-	type OnKilledAccount = ();
+	type OnKilledAccount = Vesting;
 	type OnNewAccount = ();
 	type OnSetCode = ();
 	type Origin = Origin;
@@ -80,28 +87,227 @@ impl pallet_balances::Config for Test {
 	type Event = Event;
 	type ExistentialDeposit = ExistentialDeposit;
 	type MaxLocks = MaxLocks;
-	type MaxReserves = ();
+	type MaxReserves = MaxReserves;
 	type ReserveIdentifier = [u8; 8];
 	type WeightInfo = ();
 }
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = ();
+	type MetadataDepositBase = ();
+	type MetadataDepositPerByte = ();
+	type ApprovalDeposit = ();
+	type StringLimit = ();
+	type Freezer = ();
+	type WeightInfo = ();
+	type Extra = ();
+}
+impl pallet_uniques::Config for Test {
+	type Event = Event;
+	type ClassId = u32;
+	type InstanceId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type ClassDeposit = ();
+	type InstanceDeposit = ();
+	type MetadataDepositBase = ();
+	type AttributeDepositBase = ();
+	type DepositPerByte = ();
+	type StringLimit = ();
+	type KeyLimit = ();
+	type ValueLimit = ();
+	type WeightInfo = ();
+}
 parameter_types! {
-	pub const MinVestedTransfer: u64 = 256 * 2;
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+impl pallet_scheduler::Config for Test {
+	type Call = Call;
+	type Event = Event;
+	type MaxScheduledPerBlock = ();
+	type MaximumWeight = MaximumSchedulerWeight;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type ScheduleOrigin = frame_system::EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub static MinVestedTransfer: u64 = 256 * 2;
+	pub static MaxIncomingSchedules: u32 = 2;
+	pub const IncomingSchedulePeriod: u64 = 10;
+	pub static ScheduleDeposit: u64 = 0;
+	pub static MaxTotalVested: Option<u64> = None;
+	pub static TransferPolicy: crate::MinVestedTransferPolicy = Default::default();
 	pub static ExistentialDeposit: u64 = 0;
+	pub static ArchiveCompletedSchedules: bool = false;
+	pub const MaxArchived: u32 = 2;
+	pub const UnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+	pub const MaxUnsignedValidity: u64 = 10;
+	pub static Unverified: Vec<u64> = vec![];
+	pub static BonusRate: Perbill = Perbill::zero();
+	pub const BonusPot: u64 = 99;
+	pub const PositionClass: u32 = 0;
+	pub const ContingencyPeriod: u64 = 10;
+	pub static SweepDelay: Option<u64> = Some(20);
+	pub static SweepDestination: Option<u64> = Some(98);
+	pub static UnlockAllowed: bool = true;
+	pub static PermissionedVestOther: bool = false;
+	pub static NoOpVestWhenNotVesting: bool = false;
+	pub const ShadowAssetId: u32 = 0;
+	pub const AutoVestLimit: u32 = 2;
+	pub static MaxAutoVestsPerBlock: u32 = 2;
+	pub static EarlyExitPenalty: Perbill = Perbill::from_percent(10);
+	pub const MaxClaimProofLength: u32 = 16;
+	pub const MaxMetadataLen: u32 = 8;
+	pub MaxUnlockMultiplier: FixedU128 = FixedU128::saturating_from_integer(10);
+	pub const MaxReserves: u32 = 2;
+	pub const VestingReserveId: [u8; 8] = *b"vesting ";
+}
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where Call: From<C> {
+	type OverarchingCall = Call;
+	type Extrinsic = UncheckedExtrinsic;
 }
 impl Config for Test {
-	type BlockNumberToBalance = Identity;
+	type ArchiveCompletedSchedules = ArchiveCompletedSchedules;
+	type MaxArchived = MaxArchived;
 	type Currency = Balances;
+	type ReserveId = VestingReserveId;
 	type Event = Event;
 	type MinVestedTransfer = MinVestedTransfer;
+	type MaxTotalVested = MaxTotalVested;
+	type MinVestedTransferPolicy = TransferPolicy;
+	type MaxIncomingSchedules = MaxIncomingSchedules;
+	type IncomingSchedulePeriod = IncomingSchedulePeriod;
+	type ScheduleDeposit = ScheduleDeposit;
+	type OffchainPublic = UintAuthorityId;
+	type OffchainSignature = TestSignature;
+	type OnVestingCompleted = RecordCompleted;
+	type OnVestUnlocked = RecordUnlocked;
+	type OnLockedChanged = ShadowAsset<Assets, ShadowAssetId>;
+	type PalletsOrigin = OriginCaller;
+	type ScheduledCall = Call;
+	type Scheduler = Scheduler;
+	type UnsignedPriority = UnsignedPriority;
+	type MaxUnsignedValidity = MaxUnsignedValidity;
+	type AutoVestLimit = AutoVestLimit;
+	type MaxAutoVestsPerBlock = MaxAutoVestsPerBlock;
+	type BalanceNeeded = TransferredBalance;
+	type VestPrecondition = Verified;
+	type UnlockOracle = MockOracle;
+	type BonusRate = BonusRate;
+	type BonusPayout = crate::PayFromAccount<Test, BonusPot>;
+	type Positions = Uniques;
+	type PositionClass = PositionClass;
+	type ContingencyPeriod = ContingencyPeriod;
+	type SweepDelay = SweepDelay;
+	type SweepDestination = SweepDestination;
+	type EarlyExitPenalty = EarlyExitPenalty;
+	type EarlyExitPenaltyHandler = ();
+	type ClaimsPayout = crate::PayByMinting<Test>;
+	type Beneficiaries = TestBeneficiaries;
+	type MaxClaimProofLength = MaxClaimProofLength;
+	type MaxMetadataLen = MaxMetadataLen;
+	type PermissionedVestOther = PermissionedVestOther;
+	type NoOpVestWhenNotVesting = NoOpVestWhenNotVesting;
+	type VestOperators = VestOperators;
+	type VestedTransferOrigin = frame_system::EnsureSigned<u64>;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type UnlockMultiplierOrigin = frame_system::EnsureRoot<u64>;
+	type MaxUnlockMultiplier = MaxUnlockMultiplier;
 	type WeightInfo = ();
 }
 
+/// Every account except those in `Unverified`.
+pub struct Verified;
+impl Contains<u64> for Verified {
+	fn contains(who: &u64) -> bool {
+		!Unverified::get().contains(who)
+	}
+}
+
+frame_support::match_type! {
+	pub type VestOperators: impl Contains<u64> = { 42 };
+}
+
+/// The value of balance transfers, and nothing for any other call.
+pub struct TransferredBalance;
+impl BalanceNeeded<u64, Call, u64> for TransferredBalance {
+	fn balance_needed(_: &u64, call: &Call) -> u64 {
+		match call {
+			Call::Balances(pallet_balances::Call::transfer(_, value)) => *value,
+			_ => 0,
+		}
+	}
+}
+
+/// Allows unlocking while `UnlockAllowed` is set.
+pub struct MockOracle;
+impl UnlockOracle<u64, u64, u64> for MockOracle {
+	fn can_unlock(_: &u64, _: &VestingInfo<u64, u64>) -> bool {
+		UnlockAllowed::get()
+	}
+}
+
+/// Derives account `1_000 * owner + index` for derivatives and `2_000 + location` for the
+/// sovereign account of any location but `0`.
+pub struct TestBeneficiaries;
+impl DeriveBeneficiary<u64> for TestBeneficiaries {
+	type Location = u32;
+
+	fn derivative_account(owner: &u64, index: u16) -> Option<u64> {
+		Some(1_000 * owner + index as u64)
+	}
+
+	fn sovereign_account(location: &u32) -> Option<u64> {
+		if *location == 0 {
+			None
+		} else {
+			Some(2_000 + *location as u64)
+		}
+	}
+}
+
+thread_local! {
+	pub static COMPLETED: RefCell<Vec<u64>> = RefCell::new(vec![]);
+	pub static UNLOCKED: RefCell<Vec<(u64, u64)>> = RefCell::new(vec![]);
+}
+
+/// Records every account whose vesting has completed in `COMPLETED`.
+pub struct RecordCompleted;
+impl OnVestingCompleted<u64> for RecordCompleted {
+	fn on_vesting_completed(who: &u64) {
+		COMPLETED.with(|c| c.borrow_mut().push(*who));
+	}
+}
+
 pub struct ExtBuilder {
 	existential_deposit: u64,
+	min_vested_transfer: u64,
+	max_incoming_schedules: u32,
+	max_total_vested: Option<u64>,
+	schedules: Vec<(u64, VestingInfo<u64, u64>)>,
+	endowed_schedules: Vec<(u64, VestingInfo<u64, u64>)>,
+	fractional_vesting: Vec<FractionalGenesisVesting<u64, u64>>,
+	vesting_until: Vec<GenesisVestingUntil<u64, u64, u64>>,
+	skip_invalid: bool,
 }
 impl Default for ExtBuilder {
 	fn default() -> Self {
-		Self { existential_deposit: 1 }
+		Self {
+			existential_deposit: 1,
+			min_vested_transfer: 256 * 2,
+			max_incoming_schedules: 2,
+			max_total_vested: None,
+			schedules: vec![],
+			endowed_schedules: vec![],
+			fractional_vesting: vec![],
+			vesting_until: vec![],
+			skip_invalid: false,
+		}
 	}
 }
 impl ExtBuilder {
@@ -110,8 +316,51 @@ impl ExtBuilder {
 		self
 	}
 
+	pub fn min_vested_transfer(mut self, min_vested_transfer: u64) -> Self {
+		self.min_vested_transfer = min_vested_transfer;
+		self
+	}
+
+	pub fn max_incoming_schedules(mut self, max_incoming_schedules: u32) -> Self {
+		self.max_incoming_schedules = max_incoming_schedules;
+		self
+	}
+
+	pub fn max_total_vested(mut self, max_total_vested: Option<u64>) -> Self {
+		self.max_total_vested = max_total_vested;
+		self
+	}
+
+	pub fn schedules(mut self, schedules: Vec<(u64, VestingInfo<u64, u64>)>) -> Self {
+		self.schedules = schedules;
+		self
+	}
+
+	pub fn endowed_schedules(mut self, schedules: Vec<(u64, VestingInfo<u64, u64>)>) -> Self {
+		self.endowed_schedules = schedules;
+		self
+	}
+
+	pub fn fractional_vesting(mut self, fractional_vesting: Vec<FractionalGenesisVesting<u64, u64>>) -> Self {
+		self.fractional_vesting = fractional_vesting;
+		self
+	}
+
+	pub fn vesting_until(mut self, vesting_until: Vec<GenesisVestingUntil<u64, u64, u64>>) -> Self {
+		self.vesting_until = vesting_until;
+		self
+	}
+
+	pub fn skip_invalid(mut self, skip_invalid: bool) -> Self {
+		self.skip_invalid = skip_invalid;
+		self
+	}
+
 	pub fn build(self) -> sp_io::TestExternalities {
 		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+		MIN_VESTED_TRANSFER.with(|v| *v.borrow_mut() = self.min_vested_transfer);
+		MAX_INCOMING_SCHEDULES.with(|v| *v.borrow_mut() = self.max_incoming_schedules);
+		MAX_TOTAL_VESTED.with(|v| *v.borrow_mut() = self.max_total_vested);
 		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 		pallet_balances::GenesisConfig::<Test> {
 			balances: vec![
@@ -126,10 +375,15 @@ impl ExtBuilder {
 		.unwrap();
 		pallet_vesting::GenesisConfig::<Test> {
 			vesting: vec![
-				(1, 0, 10, 5 * self.existential_deposit),
-				(2, 10, 20, 0),
-				(12, 10, 20, 5 * self.existential_deposit),
+				GenesisVesting { who: 1, begin: 0, length: 10, liquid: 5 * self.existential_deposit },
+				GenesisVesting { who: 2, begin: 10, length: 20, liquid: 0 },
+				GenesisVesting { who: 12, begin: 10, length: 20, liquid: 5 * self.existential_deposit },
 			],
+			schedules: self.schedules,
+			endowed_schedules: self.endowed_schedules,
+			fractional_vesting: self.fractional_vesting,
+			vesting_until: self.vesting_until,
+			skip_invalid: self.skip_invalid,
 		}
 		.assimilate_storage(&mut t)
 		.unwrap();
@@ -138,3 +392,11 @@ impl ExtBuilder {
 		ext
 	}
 }
+
+/// Records every unlock in `UNLOCKED`.
+pub struct RecordUnlocked;
+impl OnVestUnlocked<u64, u64> for RecordUnlocked {
+	fn on_vest_unlocked(who: &u64, amount: u64) {
+		UNLOCKED.with(|u| u.borrow_mut().push((*who, amount)));
+	}
+}
@@ -0,0 +1,100 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `Pay` trait and its implementations, used to deliver payments as vested transfers and to
+//! pay out vesting bonuses and claims.
+
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, ExistenceRequirement, Get, Imbalance},
+};
+use sp_runtime::{traits::{One, Saturating}, TokenError};
+use sp_std::marker::PhantomData;
+
+use crate::{block_number_to_balance, BalanceOf, Config, Pallet, VestingInfo};
+
+/// Can be implemented by types which pay out funds of type `Balance` to a `Beneficiary`, e.g. to
+/// deliver the spends of a treasury.
+pub trait Pay<Beneficiary, Balance> {
+	/// Make a payment of `amount` to `who`.
+	///
+	/// If the payment cannot be made, an `Err` is returned and nothing is paid.
+	fn pay(who: &Beneficiary, amount: Balance) -> DispatchResult;
+}
+
+/// Pays nothing, failing every payment.
+impl<Beneficiary, Balance> Pay<Beneficiary, Balance> for () {
+	fn pay(_: &Beneficiary, _: Balance) -> DispatchResult {
+		Err(TokenError::Unsupported.into())
+	}
+}
+
+/// Pay out funds from the `Source` account as vested transfers.
+///
+/// Each payment starts vesting `Delay` blocks after it is made and is fully vested `Duration`
+/// blocks later, so that e.g. grants paid out by a treasury come with a built-in lockup.
+///
+/// Payments are subject to the same checks as `vested_transfer`: the amount must be at least
+/// `MinVestedTransfer` and the beneficiary must not already have a vesting schedule.
+pub struct PayVested<T, Source, Delay, Duration>(PhantomData<(T, Source, Delay, Duration)>);
+
+impl<T, Source, Delay, Duration> Pay<T::AccountId, BalanceOf<T>>
+	for PayVested<T, Source, Delay, Duration>
+where
+	T: Config,
+	Source: Get<T::AccountId>,
+	Delay: Get<T::BlockNumber>,
+	Duration: Get<T::BlockNumber>,
+{
+	fn pay(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		let duration = block_number_to_balance::<_, BalanceOf<T>>(Duration::get()).max(One::one());
+		let schedule = VestingInfo {
+			locked: amount,
+			per_block: (amount / duration).max(One::one()),
+			starting_block: <frame_system::Pallet<T>>::block_number().saturating_add(Delay::get()),
+		};
+		Pallet::<T>::do_vested_transfer(&Source::get(), who, schedule, false)
+	}
+}
+
+/// Pay out funds from the `Source` account, e.g. a pot set aside for the purpose.
+///
+/// The `Source` account is kept alive.
+pub struct PayFromAccount<T, Source>(PhantomData<(T, Source)>);
+
+impl<T: Config, Source: Get<T::AccountId>> Pay<T::AccountId, BalanceOf<T>>
+	for PayFromAccount<T, Source>
+{
+	fn pay(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		T::Currency::transfer(&Source::get(), who, amount, ExistenceRequirement::KeepAlive)
+	}
+}
+
+/// Pay out funds by minting them, increasing the total issuance.
+///
+/// Payments which would leave a new account below the existential deposit fail, as nothing could
+/// be minted for them.
+pub struct PayByMinting<T>(PhantomData<T>);
+
+impl<T: Config> Pay<T::AccountId, BalanceOf<T>> for PayByMinting<T> {
+	fn pay(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		let minted = T::Currency::deposit_creating(who, amount);
+		ensure!(minted.peek() == amount, TokenError::BelowMinimum);
+		Ok(())
+	}
+}
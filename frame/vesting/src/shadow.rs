@@ -0,0 +1,48 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mirroring of the funds locked under vesting into an accounting asset.
+
+use frame_support::traits::{tokens::fungibles, Get};
+use sp_runtime::traits::Saturating;
+use sp_std::marker::PhantomData;
+
+use crate::OnLockedChanged;
+
+/// Mirror the funds locked under vesting into the `Id` asset of `Assets`, so that tools which
+/// only understand asset balances can display vesting positions.
+///
+/// The asset should be created with a minimum balance of one, or smaller locks won't be
+/// mirrored. It is not made non-transferable by this adapter: the runtime should filter the
+/// transfer calls of the asset, e.g. in its `BaseCallFilter`. Failures to mint or burn are
+/// ignored, so that vesting is never held up by the mirror.
+pub struct ShadowAsset<Assets, Id>(PhantomData<(Assets, Id)>);
+
+impl<AccountId, Assets, Id> OnLockedChanged<AccountId, Assets::Balance> for ShadowAsset<Assets, Id> where
+	Assets: fungibles::Mutate<AccountId>,
+	Id: Get<Assets::AssetId>,
+{
+	fn on_locked_changed(who: &AccountId, locked: Assets::Balance) {
+		let id = Id::get();
+		let mirrored = Assets::balance(id, who);
+		if locked > mirrored {
+			let _ = Assets::mint_into(id, who, locked.saturating_sub(mirrored));
+		} else if locked < mirrored {
+			let _ = Assets::burn_from(id, who, mirrored.saturating_sub(locked));
+		}
+	}
+}
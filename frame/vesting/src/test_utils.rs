@@ -0,0 +1,69 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Testing utils for vesting, so that pallets consuming `VestingSchedule` can set up and check
+//! vesting state in their own mock runtimes.
+
+use crate::*;
+
+/// Fund `who` with enough free balance to cover `schedule` and place it on them.
+pub fn seed_schedule<T: Config>(
+	who: &T::AccountId,
+	schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+) -> DispatchResult {
+	let free = T::Currency::free_balance(who);
+	let required = schedule.locked.saturating_add(T::Currency::minimum_balance());
+	if free < required {
+		T::Currency::make_free_balance_be(who, required);
+	}
+	Pallet::<T>::add_vesting_schedule(
+		who,
+		schedule.locked,
+		schedule.per_block,
+		schedule.starting_block,
+	)
+}
+
+/// Move to block `n` and unlock the funds `who` has vested by then.
+pub fn vest_at<T: Config>(who: &T::AccountId, n: T::BlockNumber) -> DispatchResultWithPostInfo {
+	frame_system::Pallet::<T>::set_block_number(n);
+	Pallet::<T>::do_vest(who.clone())
+}
+
+/// Assert that `locked` of `who`'s funds are still locked under vesting, or that they have no
+/// schedule left if `locked` is `None`.
+pub fn assert_locked<T: Config>(who: &T::AccountId, locked: Option<BalanceOf<T>>) {
+	assert_eq!(Pallet::<T>::vesting_balance(who), locked, "unexpected vesting balance");
+}
+
+/// Assert that `who` may transfer exactly `amount` of their free balance, given their locks.
+pub fn assert_transferable<T: Config>(who: &T::AccountId, amount: BalanceOf<T>) {
+	let free = T::Currency::free_balance(who);
+	let can_withdraw = |amount: BalanceOf<T>| {
+		T::Currency::ensure_can_withdraw(
+			who,
+			amount,
+			WithdrawReasons::TRANSFER,
+			free.saturating_sub(amount),
+		)
+		.is_ok()
+	};
+	assert!(can_withdraw(amount), "{:?} should be transferable", amount);
+	if amount < free {
+		assert!(!can_withdraw(amount.saturating_add(One::one())), "more than {:?} is transferable", amount);
+	}
+}
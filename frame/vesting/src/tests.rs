@@ -17,10 +17,10 @@
 
 use frame_support::{assert_noop, assert_ok};
 use frame_system::RawOrigin;
-use sp_runtime::traits::BadOrigin;
+use sp_runtime::traits::{BadOrigin, SignedExtension};
 
 use super::*;
-use crate::mock::{Balances, ExtBuilder, System, Test, Vesting};
+use crate::mock::{Assets, Balances, ExtBuilder, Scheduler, System, Test, Uniques, Vesting};
 
 #[test]
 fn check_vesting_status() {
@@ -357,3 +357,2583 @@ fn force_vested_transfer_correctly_fails() {
 			assert_eq!(user4_free_balance, 256 * 40);
 		});
 }
+
+#[test]
+fn vest_without_schedule_fails_by_default() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			// Account 3 has no vesting schedule.
+			assert_eq!(Vesting::vesting(&3), None);
+			assert_noop!(Vesting::vest(Some(3).into()), Error::<Test>::NotVesting);
+			assert_noop!(Vesting::vest_other(Some(3).into(), 4), Error::<Test>::NotVesting);
+		});
+}
+
+#[test]
+fn vest_without_schedule_is_free_no_op_when_enabled() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			crate::mock::NoOpVestWhenNotVesting::set(true);
+			// Account 3 has no vesting schedule.
+			assert_eq!(Vesting::vesting(&3), None);
+			let info = Vesting::vest(Some(3).into()).unwrap();
+			assert_eq!(info.pays_fee, Pays::No);
+			let info = Vesting::vest_other(Some(3).into(), 4).unwrap();
+			assert_eq!(info.pays_fee, Pays::No);
+			// Nothing happened.
+			assert!(System::events().is_empty());
+
+			// Vesting an account with a schedule is still charged.
+			let info = Vesting::vest(Some(1).into()).unwrap();
+			assert_eq!(info.pays_fee, Pays::Yes);
+		});
+}
+
+#[test]
+fn completed_schedules_are_archived_when_enabled() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let user12_vesting_schedule = Vesting::vesting(&12).unwrap();
+
+			// Archiving is disabled by default.
+			System::set_block_number(30);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::vesting(&1), None);
+			assert!(Vesting::completed_vesting(&1).is_empty());
+
+			crate::mock::ARCHIVE_COMPLETED_SCHEDULES.with(|v| *v.borrow_mut() = true);
+			assert_ok!(Vesting::vest(Some(12).into()));
+			assert_eq!(Vesting::vesting(&12), None);
+			assert_eq!(Vesting::completed_vesting(&12), vec![(user12_vesting_schedule, 30)]);
+
+			// Only the latest `MaxArchived` schedules are kept.
+			let schedule = |start| VestingInfo { locked: 20, per_block: 10, starting_block: start };
+			for start in [30, 32] {
+				assert_ok!(<Vesting as VestingSchedule<u64>>::add_vesting_schedule(
+					&12, 20, 10, start,
+				));
+				System::set_block_number(start + 2);
+				assert_ok!(Vesting::vest(Some(12).into()));
+			}
+			assert_eq!(
+				Vesting::completed_vesting(&12),
+				vec![(schedule(30), 32), (schedule(32), 34)],
+			);
+		});
+}
+
+#[test]
+fn lifetime_totals_are_tracked() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			// Genesis schedules are accounted for.
+			let totals = Vesting::lifetime_totals(&1);
+			assert_eq!(totals, VestingTotals { schedules: 1, received: 256 * 5, vested: 0, revoked: 0 });
+
+			// Account 1 has 128 units vested at block 1.
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::lifetime_totals(&1).vested, 128);
+			assert_eq!(Vesting::lifetime_totals(&1).locked(), 128 * 9);
+
+			System::set_block_number(10);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::lifetime_totals(&1).vested, 256 * 5);
+			assert_eq!(Vesting::lifetime_totals(&1).locked(), 0);
+
+			// A second schedule adds to the totals.
+			let new_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 64,
+				starting_block: 10,
+			};
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 1, new_vesting_schedule));
+			let totals = Vesting::lifetime_totals(&1);
+			assert_eq!(totals, VestingTotals { schedules: 2, received: 256 * 10, vested: 256 * 5, revoked: 0 });
+
+			// Removing a schedule part way through counts the remainder as revoked.
+			System::set_block_number(15);
+			Vesting::remove_vesting_schedule(&2);
+			assert_eq!(Vesting::vesting(&2), None);
+			let totals = Vesting::lifetime_totals(&2);
+			assert_eq!(totals, VestingTotals { schedules: 1, received: 256 * 20, vested: 256 * 5, revoked: 256 * 15 });
+			// And the lock is gone.
+			assert_ok!(Balances::transfer(Some(2).into(), 3, 256 * 20));
+		});
+}
+
+#[test]
+fn on_vesting_completed_is_called() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			// Partially vesting doesn't complete anything.
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert!(crate::mock::COMPLETED.with(|c| c.borrow().is_empty()));
+
+			System::set_block_number(10);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_ok!(Vesting::vest_other(Some(1).into(), 2));
+			assert_eq!(crate::mock::COMPLETED.with(|c| c.borrow().clone()), vec![1]);
+		});
+}
+
+#[test]
+fn on_vest_unlocked_reports_amount() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			// Account 1 has 128 units vested at block 1.
+			assert_ok!(Vesting::vest(Some(1).into()));
+			// Nothing more to unlock in the same block.
+			assert_ok!(Vesting::vest(Some(1).into()));
+			// Account 2 has not started vesting yet.
+			assert_ok!(Vesting::vest(Some(2).into()));
+
+			System::set_block_number(5);
+			assert_ok!(Vesting::vest_other(Some(3).into(), 1));
+			assert_eq!(
+				crate::mock::UNLOCKED.with(|u| u.borrow().clone()),
+				vec![(1, 128), (1, 128 * 4)],
+			);
+		});
+}
+
+#[test]
+fn vested_transfer_trait_works() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			assert_ok!(<Vesting as VestedTransfer<u64>>::vested_transfer(&3, &4, 256 * 5, 64, 10));
+			assert_eq!(
+				Vesting::vesting(&4),
+				Some(VestingInfo { locked: 256 * 5, per_block: 64, starting_block: 10 }),
+			);
+			assert_eq!(Balances::free_balance(&3), 256 * 25);
+			assert_eq!(Vesting::vesting_balance(&4), Some(256 * 5));
+
+			// The same checks as the dispatchable apply.
+			assert_noop!(
+				<Vesting as VestedTransfer<u64>>::vested_transfer(&3, &2, 256 * 5, 64, 10),
+				Error::<Test>::ExistingVestingSchedule,
+			);
+			assert_noop!(
+				<Vesting as VestedTransfer<u64>>::vested_transfer(&3, &5, 256, 64, 10),
+				Error::<Test>::AmountLow,
+			);
+		});
+}
+
+#[test]
+fn can_add_vesting_schedule_checks_params_and_existing_schedule() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			assert_ok!(Vesting::can_add_vesting_schedule(&4, 256 * 5, 64, 10));
+			assert_noop!(
+				Vesting::can_add_vesting_schedule(&2, 256 * 5, 64, 10),
+				Error::<Test>::ExistingVestingSchedule,
+			);
+			assert_noop!(
+				Vesting::can_add_vesting_schedule(&4, 0, 64, 10),
+				Error::<Test>::InvalidScheduleParams,
+			);
+			assert_noop!(
+				Vesting::can_add_vesting_schedule(&4, 256 * 5, 0, 10),
+				Error::<Test>::InvalidScheduleParams,
+			);
+
+			// A schedule which never unlocks is refused before any funds move.
+			let never_unlocks = VestingInfo {
+				locked: 256 * 5,
+				per_block: 0,
+				starting_block: 10,
+			};
+			assert_noop!(
+				Vesting::vested_transfer(Some(3).into(), 4, never_unlocks),
+				Error::<Test>::InvalidScheduleParams,
+			);
+			assert_eq!(Balances::free_balance(&3), 256 * 30);
+		});
+}
+
+#[test]
+fn vesting_inspect_returns_schedule() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			assert_eq!(
+				<Vesting as VestingInspect<u64>>::vesting_schedule(&2),
+				Some(VestingInfo { locked: 256 * 20, per_block: 256, starting_block: 10 }),
+			);
+			assert_eq!(<Vesting as VestingInspect<u64>>::vesting_schedule(&3), None);
+		});
+}
+
+#[test]
+fn vesting_balance_at_projects_future_blocks() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			// Account 2 vests 256 per block from block 10 until block 30.
+			assert_eq!(Vesting::vesting_balance_at(&2, 1), Some(256 * 20));
+			assert_eq!(Vesting::vesting_balance_at(&2, 20), Some(256 * 10));
+			assert_eq!(Vesting::vesting_balance_at(&2, 30), Some(0));
+			// Account 12 only ever has its illiquid funds locked.
+			assert_eq!(Vesting::vesting_balance_at(&12, 10), Some(256 * 5));
+			assert_eq!(<Vesting as VestingInspect<u64>>::vesting_balance_at(&12, 20), Some(256 * 5 - 64 * 10));
+			assert_eq!(Vesting::vesting_balance_at(&3, 20), None);
+		});
+}
+
+#[test]
+fn on_slash_scales_down_schedule() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			// Account 2 vests 256 per block from block 10 until block 30.
+			System::set_block_number(20);
+			assert_eq!(Vesting::vesting_balance(&2), Some(256 * 10));
+
+			// Half of what is still locked is slashed.
+			assert_ok!(Vesting::vest(Some(2).into()));
+			assert_ok!(Balances::transfer(Some(2).into(), 3, 256 * 10));
+			let _ = Balances::slash(&2, 256 * 5);
+			assert_eq!(Vesting::on_slash(&2, 256 * 5), 256 * 5);
+
+			let schedule = VestingInfo { locked: 256 * 5, per_block: 128, starting_block: 20 };
+			assert_eq!(Vesting::vesting(&2), Some(schedule));
+			assert_eq!(Vesting::vesting_balance(&2), Some(256 * 5));
+			// The schedule still ends at block 30.
+			assert_eq!(Vesting::vesting_balance_at(&2, 25), Some(128 * 5));
+			assert_eq!(Vesting::vesting_balance_at(&2, 30), Some(0));
+			assert_eq!(Vesting::lifetime_totals(&2).revoked, 256 * 5);
+
+			// Slashing more than is locked completes the schedule.
+			assert_eq!(Vesting::on_slash(&2, 256 * 10), 256 * 5);
+			assert_eq!(Vesting::vesting(&2), None);
+			// Accounts which are not vesting are unaffected.
+			assert_eq!(Vesting::on_slash(&3, 256), 0);
+		});
+}
+
+#[test]
+fn reaped_accounts_lose_their_schedule() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			assert!(Vesting::vesting(&2).is_some());
+			<Vesting as OnKilledAccount<u64>>::on_killed_account(&2);
+			assert_eq!(Vesting::vesting(&2), None);
+			assert_eq!(Vesting::lifetime_totals(&2).revoked, 256 * 20);
+		});
+}
+
+#[test]
+fn remove_orphaned_vesting_works() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let schedule = VestingInfo { locked: 256 * 5, per_block: 64, starting_block: 10 };
+			// Account 5 has no funds but has a vesting schedule left behind.
+			crate::Vesting::<Test>::insert(&5, VersionedVestingInfo::V1(schedule));
+
+			// Accounts with funds or without schedules can't be cleaned up.
+			assert_noop!(Vesting::remove_orphaned_vesting(Some(3).into(), 2), Error::<Test>::NotOrphaned);
+			assert_noop!(Vesting::remove_orphaned_vesting(Some(3).into(), 6), Error::<Test>::NotVesting);
+
+			let info = Vesting::remove_orphaned_vesting(Some(3).into(), 5).unwrap();
+			assert_eq!(info.pays_fee, Pays::No);
+			assert_eq!(Vesting::vesting(&5), None);
+		});
+}
+
+#[test]
+fn auto_vest_unlocks_before_dispatch() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let call = crate::mock::Call::Balances(pallet_balances::Call::transfer(2, 55));
+			let info = Default::default();
+
+			// Account 1 has only 5 units vested at block 1 (plus 50 unvested), which it doesn't
+			// need to transfer 50.
+			let small = crate::mock::Call::Balances(pallet_balances::Call::transfer(2, 50));
+			assert_ok!(AutoVest::<Test>::new().pre_dispatch(&1, &small, &info, 0));
+			assert!(Vesting::can_vest(&1));
+			// Only the reads of the check are accounted for, which are free in the mock.
+			assert_eq!(System::block_weight().total(), 0);
+
+			// It does need them to transfer 55.
+			assert_ok!(AutoVest::<Test>::new().pre_dispatch(&1, &call, &info, 0));
+			assert!(!Vesting::can_vest(&1));
+			assert_eq!(Vesting::lifetime_totals(&1).vested, 5);
+			assert!(System::block_weight().total() > 0);
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+
+			// Nothing happens for accounts which have nothing more to vest.
+			System::reset_events();
+			assert_ok!(AutoVest::<Test>::new().pre_dispatch(&1, &call, &info, 0));
+			assert_ok!(AutoVest::<Test>::new().pre_dispatch(&3, &call, &info, 0));
+			assert!(System::events().is_empty());
+		});
+}
+
+#[test]
+fn vest_unsigned_works() {
+	use frame_support::unsigned::ValidateUnsigned;
+	use sp_runtime::{
+		testing::TestSignature,
+		transaction_validity::{InvalidTransaction, TransactionSource},
+	};
+
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let signature = TestSignature(1, Vesting::vest_payload(&1, 0, 5));
+			let call = crate::Call::vest_unsigned(1, 0, 5, signature.clone());
+			assert_ok!(Vesting::validate_unsigned(TransactionSource::External, &call));
+
+			// Signed by the wrong account, or over the wrong payload.
+			let forged = TestSignature(2, Vesting::vest_payload(&1, 0, 5));
+			let forged = crate::Call::vest_unsigned(1, 0, 5, forged);
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &forged),
+				InvalidTransaction::BadProof.into(),
+			);
+			let extended = crate::Call::vest_unsigned(1, 0, 6, signature.clone());
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &extended),
+				InvalidTransaction::BadProof.into(),
+			);
+
+			// Signatures may not be valid for more than `MaxUnsignedValidity` blocks.
+			let long = TestSignature(1, Vesting::vest_payload(&1, 0, 12));
+			let long = crate::Call::vest_unsigned(1, 0, 12, long);
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &long),
+				InvalidTransaction::BadProof.into(),
+			);
+
+			// Nonces must be used in order.
+			let next = TestSignature(1, Vesting::vest_payload(&1, 1, 5));
+			let next = crate::Call::vest_unsigned(1, 1, 5, next);
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &next),
+				InvalidTransaction::Future.into(),
+			);
+
+			assert_ok!(Vesting::vest_unsigned(RawOrigin::None.into(), 1, 0, 5, signature));
+			assert_eq!(Vesting::unsigned_vest_nonce(&1), 1);
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+
+			// Nothing left to unlock in this block.
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &next),
+				InvalidTransaction::Stale.into(),
+			);
+			// The signature can't be replayed once more funds have vested.
+			System::set_block_number(3);
+			assert!(Vesting::can_vest(&1));
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &call),
+				InvalidTransaction::Stale.into(),
+			);
+			assert_ok!(Vesting::validate_unsigned(TransactionSource::External, &next));
+
+			// The signature expires.
+			System::set_block_number(6);
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &next),
+				InvalidTransaction::Stale.into(),
+			);
+		});
+}
+
+#[test]
+fn schedule_vested_transfer_works() {
+	use frame_support::traits::OnInitialize;
+
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let new_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 64,
+				starting_block: 10,
+			};
+			assert_ok!(Vesting::schedule_vested_transfer(Some(3).into(), 5, 4, new_vesting_schedule));
+			// The funds are reserved until the scheduled block.
+			assert_eq!(Vesting::vesting(&4), None);
+			assert_eq!(Balances::free_balance(&3), 256 * 25);
+			assert_eq!(Balances::reserved_balance(&3), 256 * 5);
+			assert!(Vesting::scheduled_transfers(&3, 0).is_some());
+
+			System::set_block_number(5);
+			<Scheduler as OnInitialize<u64>>::on_initialize(5);
+			assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+			assert_eq!(Balances::free_balance(&3), 256 * 25);
+			assert_eq!(Balances::reserved_balance(&3), 0);
+			assert_eq!(Vesting::vesting_balance(&4), Some(256 * 5));
+			assert_eq!(Vesting::scheduled_transfers(&3, 0), None);
+			System::assert_has_event(
+				Event::ScheduledTransferExecuted { source: 3, index: 0, result: Ok(()) }.into(),
+			);
+
+			// A transfer which fails when it is made only has its funds unreserved.
+			assert_ok!(Vesting::schedule_vested_transfer(Some(3).into(), 6, 4, new_vesting_schedule));
+			assert_eq!(Balances::reserved_balance(&3), 256 * 5);
+			System::set_block_number(6);
+			<Scheduler as OnInitialize<u64>>::on_initialize(6);
+			assert_eq!(Balances::free_balance(&3), 256 * 25);
+			assert_eq!(Balances::reserved_balance(&3), 0);
+			System::assert_has_event(Event::ScheduledTransferExecuted {
+				source: 3,
+				index: 1,
+				result: Err(Error::<Test>::ExistingVestingSchedule.into()),
+			}.into());
+
+			// A cancelled transfer is never made.
+			assert_ok!(Vesting::schedule_vested_transfer(Some(3).into(), 8, 2, new_vesting_schedule));
+			assert_noop!(
+				Vesting::cancel_scheduled_transfer(Some(4).into(), 2),
+				Error::<Test>::UnknownScheduledTransfer,
+			);
+			assert_ok!(Vesting::cancel_scheduled_transfer(Some(3).into(), 2));
+			assert_eq!(Balances::free_balance(&3), 256 * 25);
+			assert_eq!(Balances::reserved_balance(&3), 0);
+			assert_eq!(Vesting::scheduled_transfers(&3, 2), None);
+			System::set_block_number(8);
+			<Scheduler as OnInitialize<u64>>::on_initialize(8);
+			assert_eq!(Balances::free_balance(&3), 256 * 25);
+			assert_noop!(
+				Vesting::cancel_scheduled_transfer(Some(3).into(), 2),
+				Error::<Test>::UnknownScheduledTransfer,
+			);
+
+			// Invalid transfers are refused up front.
+			let new_vesting_schedule_too_low = VestingInfo {
+				locked: 256 * 1,
+				per_block: 64,
+				starting_block: 10,
+			};
+			assert_noop!(
+				Vesting::schedule_vested_transfer(Some(3).into(), 10, 4, new_vesting_schedule_too_low),
+				Error::<Test>::AmountLow,
+			);
+		});
+}
+
+#[test]
+fn vest_requires_precondition() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			crate::mock::UNVERIFIED.with(|v| v.borrow_mut().push(1));
+			assert!(!Vesting::can_vest(&1));
+			assert_noop!(Vesting::vest(Some(1).into()), Error::<Test>::VestPreconditionUnmet);
+			assert_noop!(Vesting::vest_other(Some(2).into(), 1), Error::<Test>::VestPreconditionUnmet);
+			// Accounts which are not vesting still fail with `NotVesting`.
+			crate::mock::UNVERIFIED.with(|v| v.borrow_mut().push(3));
+			assert_noop!(Vesting::vest(Some(3).into()), Error::<Test>::NotVesting);
+
+			crate::mock::UNVERIFIED.with(|v| v.borrow_mut().clear());
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+		});
+}
+
+#[test]
+fn accept_terms_works() {
+	use sp_core::H256;
+	use sp_runtime::testing::TestSignature;
+
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let statement_hash = H256::repeat_byte(1);
+			assert_noop!(
+				Vesting::require_terms(Some(1).into(), 1, statement_hash),
+				BadOrigin,
+			);
+			assert_ok!(Vesting::require_terms(RawOrigin::Root.into(), 1, statement_hash));
+			assert!(!Vesting::can_vest(&1));
+			assert_noop!(Vesting::vest(Some(1).into()), Error::<Test>::TermsNotAccepted);
+
+			// Signed by the wrong account, or over the wrong statement.
+			assert_noop!(
+				Vesting::accept_terms(Some(1).into(), TestSignature(2, statement_hash.as_ref().to_vec())),
+				Error::<Test>::InvalidStatementSignature,
+			);
+			assert_noop!(
+				Vesting::accept_terms(Some(1).into(), TestSignature(1, vec![2; 32])),
+				Error::<Test>::InvalidStatementSignature,
+			);
+			assert_noop!(
+				Vesting::accept_terms(Some(2).into(), TestSignature(2, statement_hash.as_ref().to_vec())),
+				Error::<Test>::NoTermsPending,
+			);
+
+			assert_ok!(
+				Vesting::accept_terms(Some(1).into(), TestSignature(1, statement_hash.as_ref().to_vec()))
+			);
+			assert_eq!(Vesting::pending_terms(&1), None);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+
+			// Terms may also be lifted by `ForceOrigin`.
+			assert_ok!(Vesting::require_terms(RawOrigin::Root.into(), 12, statement_hash));
+			assert_noop!(Vesting::clear_terms(Some(12).into(), 12), BadOrigin);
+			assert_ok!(Vesting::clear_terms(RawOrigin::Root.into(), 12));
+			System::assert_last_event(crate::Event::<Test>::TermsCleared { account: 12 }.into());
+			assert_eq!(Vesting::pending_terms(&12), None);
+			assert_noop!(
+				Vesting::clear_terms(RawOrigin::Root.into(), 12),
+				Error::<Test>::NoTermsPending,
+			);
+		});
+}
+
+#[test]
+fn unvested_balance_works() {
+	use frame_support::traits::UnvestedBalance;
+
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			// Account 1 vests 5 per block from block 0; account 3 is not vesting.
+			assert_eq!(<Vesting as UnvestedBalance<u64, u64>>::unvested_balance(&1), 45);
+			assert_eq!(<Vesting as UnvestedBalance<u64, u64>>::unvested_balance(&3), 0);
+			assert_eq!(<() as UnvestedBalance<u64, u64>>::unvested_balance(&1), 0);
+
+			System::set_block_number(2);
+			assert_eq!(<Vesting as UnvestedBalance<u64, u64>>::unvested_balance(&1), 40);
+		});
+}
+
+#[test]
+fn vest_while_bonded_works() {
+	use frame_support::traits::{LockableCurrency, WithdrawReasons};
+	const STAKING_ID: [u8; 8] = *b"staking ";
+
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			// Account 1 has 100, of which 45 is still unvested; all of it may be bonded.
+			assert_eq!(Vesting::stakeable_unvested(&1), 45);
+			assert_eq!(Vesting::stakeable_unvested(&3), 0);
+			Balances::set_lock(STAKING_ID, &1, 100, WithdrawReasons::all());
+			assert_eq!(Vesting::stakeable_unvested(&1), 45);
+
+			// Vesting while bonded reduces the vesting lock, but the bond still applies.
+			System::set_block_number(5);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::stakeable_unvested(&1), 25);
+			assert_noop!(
+				Balances::transfer(Some(1).into(), 2, 1),
+				pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+			);
+
+			// Unbonding part of the stake frees up the vested funds only.
+			Balances::set_lock(STAKING_ID, &1, 50, WithdrawReasons::all());
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 50));
+			assert_noop!(
+				Balances::transfer(Some(1).into(), 2, 1),
+				pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+			);
+
+			// The bond outlasts the vesting schedule.
+			System::set_block_number(10);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::vesting(&1), None);
+			assert_eq!(Vesting::stakeable_unvested(&1), 0);
+			assert_noop!(
+				Balances::transfer(Some(1).into(), 2, 1),
+				pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+			);
+			Balances::remove_lock(STAKING_ID, &1);
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 40));
+		});
+}
+
+#[test]
+fn vesting_proxy_filter_works() {
+	use crate::mock::Call;
+	use frame_support::traits::Filter;
+
+	type VestingProxy = crate::VestingProxy<Test>;
+	assert!(VestingProxy::filter(&Call::Vesting(crate::Call::vest())));
+	assert!(VestingProxy::filter(&Call::Vesting(crate::Call::vest_other(2))));
+	assert!(!VestingProxy::filter(&Call::Vesting(crate::Call::remove_orphaned_vesting(2))));
+	assert!(!VestingProxy::filter(&Call::Vesting(crate::Call::vested_transfer(
+		2,
+		VestingInfo { locked: 10, per_block: 1, starting_block: 0 },
+	))));
+	assert!(!VestingProxy::filter(&Call::Balances(pallet_balances::Call::transfer(2, 10))));
+}
+
+#[test]
+fn pay_vested_works() {
+	use frame_support::parameter_types;
+
+	parameter_types! {
+		pub const Treasury: u64 = 4;
+		pub const Delay: u64 = 5;
+		pub const Duration: u64 = 20;
+	}
+	type Payer = crate::PayVested<Test, Treasury, Delay, Duration>;
+
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			assert_ok!(Payer::pay(&3, 1024));
+			assert_eq!(Balances::free_balance(&4), 256 * 36);
+			assert_eq!(
+				Vesting::vesting(&3),
+				Some(VestingInfo { locked: 1024, per_block: 51, starting_block: 6 }),
+			);
+			assert_eq!(Vesting::vesting_balance(&3), Some(1024));
+
+			// The beneficiary may only be paid while not already vesting.
+			assert_noop!(Payer::pay(&3, 1024), Error::<Test>::ExistingVestingSchedule);
+			assert_noop!(Payer::pay(&5, 256), Error::<Test>::AmountLow);
+		});
+}
+
+#[test]
+fn pay_by_minting_works() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let issuance = Balances::total_issuance();
+			assert_ok!(crate::PayByMinting::<Test>::pay(&20, 256));
+			assert_eq!(Balances::free_balance(&20), 256);
+			assert_eq!(Balances::total_issuance(), issuance + 256);
+
+			// Nothing can be minted for new accounts below the existential deposit.
+			assert_noop!(
+				crate::PayByMinting::<Test>::pay(&21, 255),
+				sp_runtime::TokenError::BelowMinimum,
+			);
+
+			// `()` never pays anything, for runtimes opting out of payouts.
+			assert_noop!(<() as Pay<u64, u64>>::pay(&20, 256), sp_runtime::TokenError::Unsupported);
+		});
+}
+
+#[test]
+fn standing_orders_work() {
+	use frame_support::traits::OnInitialize;
+
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			// Pay 512 to account 5 every 5 blocks, three times, each vesting fully a block later.
+			assert_ok!(Vesting::create_standing_order(Some(3).into(), 5, 512, 512, 0, 5, 3));
+			assert_noop!(
+				Vesting::create_standing_order(Some(3).into(), 5, 256, 256, 0, 5, 3),
+				Error::<Test>::AmountLow,
+			);
+			assert_noop!(
+				Vesting::create_standing_order(Some(3).into(), 5, 512, 512, 0, 0, 3),
+				Error::<Test>::InvalidScheduleParams,
+			);
+			assert_eq!(Vesting::standing_orders(&3, 0).map(|o| o.remaining), Some(3));
+
+			System::set_block_number(6);
+			<Scheduler as OnInitialize<u64>>::on_initialize(6);
+			assert_eq!(
+				Vesting::vesting(&5),
+				Some(VestingInfo { locked: 512, per_block: 512, starting_block: 6 }),
+			);
+			assert_eq!(Balances::free_balance(&3), 256 * 30 - 512);
+			assert_eq!(Vesting::standing_orders(&3, 0).map(|o| o.remaining), Some(2));
+
+			// Account 5 has not vested its previous transfer yet, so this one is skipped.
+			System::set_block_number(11);
+			<Scheduler as OnInitialize<u64>>::on_initialize(11);
+			System::assert_has_event(
+				crate::Event::<Test>::StandingOrderExecuted {
+					funder: 3,
+					index: 0,
+					result: Err(Error::<Test>::ExistingVestingSchedule.into()),
+				}.into(),
+			);
+			assert_eq!(Balances::free_balance(&3), 256 * 30 - 512);
+
+			assert_ok!(Vesting::vest(Some(5).into()));
+			assert_ok!(Vesting::modify_standing_order(Some(3).into(), 0, 5, 1024, 512, 4));
+			assert_noop!(
+				Vesting::modify_standing_order(Some(3).into(), 1, 5, 1024, 512, 4),
+				Error::<Test>::UnknownStandingOrder,
+			);
+
+			System::set_block_number(16);
+			<Scheduler as OnInitialize<u64>>::on_initialize(16);
+			assert_eq!(
+				Vesting::vesting(&5),
+				Some(VestingInfo { locked: 1024, per_block: 512, starting_block: 20 }),
+			);
+			assert_eq!(Balances::free_balance(&3), 256 * 30 - 1536);
+			assert!(Vesting::standing_orders(&3, 0).is_none());
+
+			// Cancelled orders make no further transfers.
+			assert_ok!(Vesting::create_standing_order(Some(3).into(), 4, 512, 512, 0, 5, 3));
+			assert_ok!(Vesting::cancel_standing_order(Some(3).into(), 1));
+			assert_noop!(
+				Vesting::cancel_standing_order(Some(3).into(), 1),
+				Error::<Test>::UnknownStandingOrder,
+			);
+			System::set_block_number(21);
+			<Scheduler as OnInitialize<u64>>::on_initialize(21);
+			assert_eq!(Vesting::vesting(&4), None);
+			assert_eq!(Balances::free_balance(&3), 256 * 30 - 1536);
+
+			// Orders whose task can't be cancelled are kept.
+			assert_ok!(Vesting::create_standing_order(Some(3).into(), 4, 512, 512, 0, 5, 3));
+			let (when, task) = Vesting::standing_orders(&3, 2).unwrap().task;
+			assert_ok!(Scheduler::cancel(crate::mock::Origin::root(), when, task));
+			assert_noop!(
+				Vesting::cancel_standing_order(Some(3).into(), 2),
+				Error::<Test>::UnknownStandingOrder,
+			);
+			assert!(Vesting::standing_orders(&3, 2).is_some());
+		});
+}
+
+#[test]
+fn streams_work() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			// Stream 100 from account 3 to account 4 at 10 per block after block 1.
+			assert_ok!(Vesting::create_stream(Some(3).into(), 4, 100, 10, 1));
+			assert_noop!(
+				Vesting::create_stream(Some(3).into(), 4, 100, 0, 1),
+				Error::<Test>::InvalidScheduleParams,
+			);
+			assert_eq!(Balances::reserved_balance(&3), 100);
+
+			System::set_block_number(4);
+			assert_noop!(Vesting::withdraw_from_stream(Some(3).into(), 0), Error::<Test>::NotStreamParty);
+			assert_noop!(Vesting::withdraw_from_stream(Some(4).into(), 1), Error::<Test>::UnknownStream);
+			assert_ok!(Vesting::withdraw_from_stream(Some(4).into(), 0));
+			assert_eq!(Balances::free_balance(&4), 400 + 30);
+			assert_eq!(Balances::reserved_balance(&3), 70);
+
+			// Nothing more to withdraw in the same block.
+			assert_ok!(Vesting::withdraw_from_stream(Some(4).into(), 0));
+			assert_eq!(Balances::free_balance(&4), 400 + 30);
+
+			// The rest can be withdrawn once fully streamed, after which the stream is removed.
+			System::set_block_number(20);
+			assert_ok!(Vesting::withdraw_from_stream(Some(4).into(), 0));
+			assert_eq!(Balances::free_balance(&4), 400 + 100);
+			assert_eq!(Balances::reserved_balance(&3), 0);
+			assert_eq!(Vesting::streams(0), None);
+
+			// Cancelling pays out what has been streamed and refunds the rest.
+			assert_ok!(Vesting::create_stream(Some(3).into(), 4, 100, 10, 20));
+			System::set_block_number(25);
+			assert_noop!(Vesting::cancel_stream(Some(4).into(), 1), Error::<Test>::NotStreamParty);
+			assert_ok!(Vesting::cancel_stream(Some(3).into(), 1));
+			assert_eq!(Balances::free_balance(&4), 400 + 150);
+			assert_eq!(Balances::free_balance(&3), 300 - 150);
+			assert_eq!(Balances::reserved_balance(&3), 0);
+			assert_eq!(Vesting::streams(1), None);
+		});
+}
+
+#[test]
+fn cancelling_a_stream_to_a_dead_recipient_refunds_the_sender() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		// Account 20 doesn't exist.
+		assert_ok!(Vesting::create_stream(Some(3).into(), 20, 100, 10, 1));
+		System::set_block_number(4);
+		assert_ok!(Vesting::cancel_stream(Some(3).into(), 0));
+		System::assert_last_event(
+			crate::Event::<Test>::StreamCancelled { index: 0, paid_out: 0, refunded: 70 }.into(),
+		);
+		assert_eq!(Balances::reserved_balance(&3), 30);
+
+		// The stream stops, leaving what has been streamed to be withdrawn.
+		System::set_block_number(20);
+		assert_eq!(Vesting::streams(0).map(|s| s.withdrawable_at(20)), Some(30));
+		Balances::make_free_balance_be(&20, 10);
+		assert_ok!(Vesting::withdraw_from_stream(Some(20).into(), 0));
+		assert_eq!(Balances::free_balance(&20), 40);
+		assert_eq!(Balances::reserved_balance(&3), 0);
+		assert_eq!(Vesting::streams(0), None);
+	});
+}
+
+#[test]
+fn streams_only_pay_out_their_own_reserve() {
+	use frame_support::traits::{NamedReservableCurrency, ReservableCurrency};
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		// Account 3 has funds reserved by another pallet alongside its stream.
+		assert_ok!(Balances::reserve(&3, 50));
+		assert_ok!(Vesting::create_stream(Some(3).into(), 4, 100, 10, 1));
+		let id = crate::mock::VestingReserveId::get();
+		let _ = Balances::slash_reserved_named(&id, &3, 60);
+
+		System::set_block_number(20);
+		assert_ok!(Vesting::withdraw_from_stream(Some(4).into(), 0));
+		System::assert_last_event(
+			crate::Event::<Test>::StreamWithdrawn { index: 0, amount: 40 }.into(),
+		);
+		assert_eq!(Balances::free_balance(&4), 400 + 40);
+		assert_eq!(Vesting::streams(0).map(|s| s.withdrawn), Some(40));
+		assert_eq!(Balances::reserved_balance(&3), 50);
+
+		assert_ok!(Vesting::cancel_stream(Some(3).into(), 0));
+		System::assert_last_event(
+			crate::Event::<Test>::StreamCancelled { index: 0, paid_out: 0, refunded: 0 }.into(),
+		);
+		assert_eq!(Balances::reserved_balance(&3), 50);
+	});
+}
+
+#[test]
+fn bonus_accrues_on_locked_funds() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			// Without a bonus rate nothing is paid.
+			System::set_block_number(2);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Balances::free_balance(&1), 100);
+
+			crate::mock::BONUS_RATE.with(|r| *r.borrow_mut() = Perbill::from_percent(10));
+			Balances::make_free_balance_be(&99, 1_000);
+
+			// 30 locked on average over 4 blocks.
+			System::set_block_number(6);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Balances::free_balance(&1), 100 + 12);
+			assert_eq!(Vesting::bonus_paid_until(&1), Some(6));
+
+			// 10 locked on average over 4 blocks, after which the schedule is complete.
+			System::set_block_number(10);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Balances::free_balance(&1), 100 + 12 + 4);
+			assert_eq!(Balances::free_balance(&99), 1_000 - 16);
+			assert_eq!(Vesting::bonus_paid_until(&1), None);
+
+			// The bonus is forfeited if it cannot be paid.
+			Balances::make_free_balance_be(&99, 10);
+			System::set_block_number(12);
+			assert_ok!(Vesting::vest(Some(2).into()));
+			assert_eq!(Balances::free_balance(&2), 200);
+			assert_eq!(Vesting::bonus_paid_until(&2), Some(12));
+		});
+}
+
+#[test]
+fn vesting_positions_work() {
+	use frame_support::traits::tokens::nonfungibles::Inspect;
+
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let schedule = VestingInfo { locked: 1024, per_block: 128, starting_block: 10 };
+			// The position class must exist.
+			assert!(Vesting::create_position(Some(3).into(), 4, schedule).is_err());
+			assert_ok!(Uniques::force_create(RawOrigin::Root.into(), 0, 1, true));
+
+			assert_ok!(Vesting::create_position(Some(3).into(), 4, schedule));
+			assert_eq!(Balances::reserved_balance(&3), 1024);
+			assert_eq!(<Uniques as Inspect<u64>>::owner(&0, &0), Some(4));
+
+			System::set_block_number(12);
+			assert_ok!(Vesting::vest_position(Some(1).into(), 0));
+			assert_eq!(Balances::free_balance(&4), 256 * 40 + 256);
+
+			// Vested funds follow the NFT.
+			assert_ok!(Uniques::transfer(Some(4).into(), 0, 0, 2));
+			System::set_block_number(20);
+			assert_ok!(Vesting::vest_position(Some(1).into(), 0));
+			assert_eq!(Balances::free_balance(&2), 256 * 20 + 768);
+			assert_eq!(Balances::reserved_balance(&3), 0);
+			assert_eq!(Balances::free_balance(&3), 256 * 30 - 1024);
+
+			// Fully paid out positions are removed along with their NFT.
+			assert_eq!(Vesting::positions(0), None);
+			assert_eq!(<Uniques as Inspect<u64>>::owner(&0, &0), None);
+			assert_noop!(Vesting::vest_position(Some(1).into(), 0), Error::<Test>::UnknownPosition);
+		});
+}
+
+#[test]
+fn vest_position_pays_out_only_its_reserve_and_rolls_back() {
+	use frame_support::{
+		storage::migration::remove_storage_prefix,
+		traits::{NamedReservableCurrency, ReservableCurrency},
+	};
+
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let schedule = VestingInfo { locked: 1024, per_block: 128, starting_block: 10 };
+		assert_ok!(Uniques::force_create(RawOrigin::Root.into(), 0, 1, true));
+		assert_ok!(Balances::reserve(&3, 256));
+		assert_ok!(Vesting::create_position(Some(3).into(), 4, schedule));
+		let id = crate::mock::VestingReserveId::get();
+		let _ = Balances::slash_reserved_named(&id, &3, 512);
+
+		// Only what is left of the position's reserve is paid out, not the other reserve.
+		System::set_block_number(20);
+		assert_ok!(Vesting::vest_position(Some(1).into(), 0));
+		assert_eq!(Balances::free_balance(&4), 256 * 40 + 512);
+		assert_eq!(Balances::reserved_balance(&3), 256);
+		assert_eq!(Vesting::positions(0).map(|p| p.paid_out), Some(512));
+
+		// Nothing is paid out if the NFT can't be burned once the position is fully paid out.
+		assert_ok!(Balances::reserve_named(&id, &3, 512));
+		remove_storage_prefix(b"Uniques", b"Class", &[]);
+		assert!(Vesting::vest_position(Some(1).into(), 0).is_err());
+		assert_eq!(Balances::free_balance(&4), 256 * 40 + 512);
+		assert_eq!(Vesting::positions(0).map(|p| p.paid_out), Some(512));
+	});
+}
+
+#[test]
+fn vest_requires_unlock_condition() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			crate::mock::UNLOCK_ALLOWED.with(|v| *v.borrow_mut() = false);
+			assert!(!Vesting::can_vest(&1));
+			assert_noop!(Vesting::vest(Some(1).into()), Error::<Test>::UnlockConditionUnmet);
+			assert_noop!(Vesting::vest_other(Some(2).into(), 1), Error::<Test>::UnlockConditionUnmet);
+
+			crate::mock::UNLOCK_ALLOWED.with(|v| *v.borrow_mut() = true);
+			assert!(Vesting::can_vest(&1));
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+		});
+}
+
+#[test]
+fn vested_transfer_with_plan_works() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let plan = VestingPlan { cliff: 10, duration: 20, revocable: false };
+			assert_noop!(Vesting::create_vesting_plan(Some(1).into(), plan), BadOrigin);
+			assert_noop!(
+				Vesting::create_vesting_plan(
+					RawOrigin::Root.into(),
+					VestingPlan { duration: 0, ..plan },
+				),
+				Error::<Test>::InvalidScheduleParams,
+			);
+			assert_ok!(Vesting::create_vesting_plan(RawOrigin::Root.into(), plan));
+			assert_noop!(
+				Vesting::vested_transfer_with_plan(Some(3).into(), 4, 1, 256 * 5),
+				Error::<Test>::UnknownPlan,
+			);
+
+			assert_ok!(Vesting::vested_transfer_with_plan(Some(3).into(), 4, 0, 256 * 5));
+			assert_eq!(
+				Vesting::vesting(&4),
+				Some(VestingInfo { locked: 256 * 5, per_block: 64, starting_block: 11 }),
+			);
+			assert_eq!(Vesting::plan_of(&4), Some(0));
+
+			// The plan is forgotten once the schedule completes.
+			System::set_block_number(31);
+			assert_ok!(Vesting::vest(Some(4).into()));
+			assert_eq!(Vesting::plan_of(&4), None);
+		});
+}
+
+#[test]
+fn revoke_vesting_plan_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let plan = VestingPlan { cliff: 0, duration: 16, revocable: true };
+			assert_ok!(Vesting::create_vesting_plan(RawOrigin::Root.into(), plan));
+			assert_ok!(Vesting::create_vesting_plan(
+				RawOrigin::Root.into(),
+				VestingPlan { revocable: false, ..plan },
+			));
+			Balances::make_free_balance_be(&4, 10_000);
+			for who in 5..8 {
+				assert_ok!(Vesting::vested_transfer_with_plan(Some(4).into(), who, 0, 512));
+			}
+			assert_noop!(
+				Vesting::revoke_vesting_plan(RawOrigin::Root.into(), 1, 3, 10),
+				Error::<Test>::PlanNotRevocable,
+			);
+			assert_noop!(
+				Vesting::revoke_vesting_plan(Some(1).into(), 0, 3, 10),
+				BadOrigin,
+			);
+
+			// A quarter has vested.
+			System::set_block_number(5);
+			assert_ok!(Vesting::revoke_vesting_plan(RawOrigin::Root.into(), 0, 3, 2));
+			assert_eq!(Balances::free_balance(&3), 300 + 2 * 384);
+			assert_eq!(Vesting::vesting_plans(0), None);
+			let revoked: mock::Event = crate::Event::<Test>::VestingPlanRevoked { plan: 0 }.into();
+			assert!(System::events().iter().all(|r| r.event != revoked));
+
+			// The rest are revoked by a later call.
+			assert_ok!(Vesting::revoke_vesting_plan(RawOrigin::Root.into(), 0, 3, 2));
+			assert_eq!(Balances::free_balance(&3), 300 + 3 * 384);
+			System::assert_last_event(crate::Event::<Test>::VestingPlanRevoked { plan: 0 }.into());
+			for who in 5..8 {
+				assert_eq!(Vesting::vesting(&who), None);
+				assert_eq!(Vesting::plan_of(&who), None);
+				assert_eq!(Balances::free_balance(&who), 128);
+			}
+			assert_noop!(
+				Vesting::revoke_vesting_plan(RawOrigin::Root.into(), 0, 3, 2),
+				Error::<Test>::UnknownPlan,
+			);
+		});
+}
+
+#[test]
+fn revoke_vesting_plan_recovers_what_is_left_of_slashed_funds() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let plan = VestingPlan { cliff: 0, duration: 16, revocable: true };
+			assert_ok!(Vesting::create_vesting_plan(RawOrigin::Root.into(), plan));
+			Balances::make_free_balance_be(&4, 10_000);
+			assert_ok!(Vesting::vested_transfer_with_plan(Some(4).into(), 5, 0, 512));
+
+			// 384 are still unvested, but only 200 are left after a slash.
+			System::set_block_number(5);
+			Balances::make_free_balance_be(&5, 200);
+			assert_ok!(Vesting::revoke_vesting_plan(RawOrigin::Root.into(), 0, 3, 10));
+			assert_eq!(Balances::free_balance(&3), 300 + 200);
+			assert_eq!(Balances::free_balance(&5), 0);
+			assert_eq!(Vesting::vesting(&5), None);
+			assert!(System::events().iter().any(|r| r.event ==
+				crate::Event::<Test>::VestingRevoked { account: 5, amount: 200 }.into()));
+		});
+}
+
+#[test]
+fn contingent_beneficiary_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				Vesting::set_contingent_beneficiary(Some(3).into(), Some(4)),
+				Error::<Test>::NotVesting,
+			);
+			assert_ok!(Vesting::set_contingent_beneficiary(Some(1).into(), Some(3)));
+			System::assert_last_event(
+				crate::Event::<Test>::ContingentBeneficiarySet { account: 1, contingent: Some(3) }
+					.into(),
+			);
+			assert_eq!(Vesting::contingent_beneficiary(&1), Some(3));
+
+			// Account 1's schedule fully matures at block 10.
+			System::set_block_number(19);
+			assert_noop!(
+				Vesting::claim_as_contingent(Some(3).into(), 1),
+				Error::<Test>::ContingencyPeriodNotElapsed,
+			);
+			System::set_block_number(20);
+			assert_noop!(
+				Vesting::claim_as_contingent(Some(4).into(), 1),
+				Error::<Test>::NotContingentBeneficiary,
+			);
+
+			// Claims are subject to the same conditions as vesting.
+			crate::mock::UNVERIFIED.with(|v| v.borrow_mut().push(1));
+			assert_noop!(
+				Vesting::claim_as_contingent(Some(3).into(), 1),
+				Error::<Test>::VestPreconditionUnmet,
+			);
+			crate::mock::UNVERIFIED.with(|v| v.borrow_mut().clear());
+			crate::mock::UNLOCK_ALLOWED.with(|v| *v.borrow_mut() = false);
+			assert_noop!(
+				Vesting::claim_as_contingent(Some(3).into(), 1),
+				Error::<Test>::UnlockConditionUnmet,
+			);
+			crate::mock::UNLOCK_ALLOWED.with(|v| *v.borrow_mut() = true);
+			assert_ok!(Vesting::require_terms(RawOrigin::Root.into(), 1, Default::default()));
+			assert_noop!(
+				Vesting::claim_as_contingent(Some(3).into(), 1),
+				Error::<Test>::TermsNotAccepted,
+			);
+			crate::PendingTerms::<Test>::remove(&1);
+
+			assert_ok!(Vesting::claim_as_contingent(Some(3).into(), 1));
+			System::assert_last_event(
+				crate::Event::<Test>::ContingentClaimed {
+					account: 1,
+					contingent: 3,
+					amount: 50,
+				}.into(),
+			);
+			assert_eq!(Balances::free_balance(&1), 50);
+			assert_eq!(Balances::free_balance(&3), 350);
+			assert_eq!(Vesting::vesting(&1), None);
+			assert_eq!(Vesting::contingent_beneficiary(&1), None);
+			assert_eq!(Vesting::lifetime_totals(&1).locked(), 0);
+
+			// Vesting completes a matured schedule, leaving nothing to claim.
+			assert_ok!(Vesting::set_contingent_beneficiary(Some(12).into(), Some(3)));
+			System::set_block_number(40);
+			assert_ok!(Vesting::vest(Some(12).into()));
+			assert_noop!(
+				Vesting::claim_as_contingent(Some(3).into(), 12),
+				Error::<Test>::NotContingentBeneficiary,
+			);
+		});
+}
+
+#[test]
+fn sweep_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			// Account 1's schedule fully matures at block 10.
+			System::set_block_number(29);
+			assert_noop!(Vesting::sweep(Some(4).into(), 1), Error::<Test>::NotSweepable);
+			System::set_block_number(30);
+			assert_ok!(Vesting::sweep(Some(4).into(), 1));
+			System::assert_last_event(
+				crate::Event::<Test>::VestingSwept { account: 1, amount: 50 }.into(),
+			);
+			assert_eq!(Balances::free_balance(&1), 50);
+			assert_eq!(Balances::free_balance(&98), 50);
+			assert_eq!(Vesting::vesting(&1), None);
+			assert_noop!(Vesting::sweep(Some(4).into(), 1), Error::<Test>::NotVesting);
+
+			// Without a destination the funds are only unlocked.
+			crate::mock::SWEEP_DESTINATION.with(|v| *v.borrow_mut() = None);
+			System::set_block_number(60);
+			assert_ok!(Vesting::sweep(Some(4).into(), 12));
+			System::assert_last_event(
+				crate::Event::<Test>::VestingSwept { account: 12, amount: 0 }.into(),
+			);
+			assert_eq!(Vesting::vesting_balance(&12), None);
+			assert_ok!(Balances::transfer(Some(12).into(), 3, 100));
+
+			// Sweeping is subject to the same conditions as vesting.
+			System::set_block_number(100);
+			crate::mock::UNVERIFIED.with(|v| v.borrow_mut().push(2));
+			assert_noop!(Vesting::sweep(Some(4).into(), 2), Error::<Test>::VestPreconditionUnmet);
+			crate::mock::UNVERIFIED.with(|v| v.borrow_mut().clear());
+			crate::mock::UNLOCK_ALLOWED.with(|v| *v.borrow_mut() = false);
+			assert_noop!(Vesting::sweep(Some(4).into(), 2), Error::<Test>::UnlockConditionUnmet);
+			crate::mock::UNLOCK_ALLOWED.with(|v| *v.borrow_mut() = true);
+			assert_ok!(Vesting::require_terms(RawOrigin::Root.into(), 2, Default::default()));
+			assert_noop!(Vesting::sweep(Some(4).into(), 2), Error::<Test>::TermsNotAccepted);
+
+			crate::mock::SWEEP_DELAY.with(|v| *v.borrow_mut() = None);
+			assert_noop!(Vesting::sweep(Some(4).into(), 2), Error::<Test>::SweepingDisabled);
+		});
+}
+
+#[test]
+fn unlock_multiplier_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let double = FixedU128::saturating_from_integer(2);
+			assert_noop!(Vesting::set_unlock_multiplier(Some(1).into(), double), BadOrigin);
+			assert_eq!(Vesting::unlock_multiplier(), FixedU128::one());
+			for invalid in [FixedU128::zero(), FixedU128::saturating_from_integer(11)] {
+				assert_noop!(
+					Vesting::set_unlock_multiplier(RawOrigin::Root.into(), invalid),
+					Error::<Test>::InvalidUnlockMultiplier,
+				);
+			}
+
+			// The multiplier only applies from the block at which it is set.
+			System::set_block_number(2);
+			assert_ok!(Vesting::set_unlock_multiplier(RawOrigin::Root.into(), double));
+			System::assert_last_event(
+				crate::Event::<Test>::UnlockMultiplierSet { multiplier: double }.into(),
+			);
+			assert_eq!(Vesting::vesting_clock(), (2, FixedU128::saturating_from_integer(2)));
+			assert_eq!(Vesting::vesting_balance(&1), Some(40));
+			System::set_block_number(3);
+			assert_eq!(Vesting::vesting_balance(&1), Some(30));
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::lifetime_totals(&1).locked(), 30);
+
+			// Lowering the multiplier doesn't lock again what was unlocked at the higher one.
+			assert_ok!(Vesting::set_unlock_multiplier(RawOrigin::Root.into(), FixedU128::one()));
+			assert_ok!(Vesting::vest(Some(1).into()));
+			System::assert_last_event(
+				crate::Event::<Test>::VestingUpdated { account: 1, unvested: 30 }.into(),
+			);
+			System::set_block_number(4);
+			assert_eq!(Vesting::vesting_balance(&1), Some(25));
+
+			assert_ok!(Vesting::set_unlock_multiplier(RawOrigin::Root.into(), double));
+			System::set_block_number(7);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::vesting(&1), None);
+
+			// The vesting clock is now ahead of the block number, yet schedules created since
+			// start at the block they are given.
+			assert_ok!(Vesting::set_unlock_multiplier(RawOrigin::Root.into(), FixedU128::one()));
+			assert_eq!(Vesting::vesting_clock(), (7, FixedU128::saturating_from_integer(11)));
+			Balances::make_free_balance_be(&3, 10_000);
+			let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+			assert_eq!(Vesting::schedule_status(&4).and_then(|s| s.ending_block), Some(18));
+			System::set_block_number(10);
+			assert_eq!(Vesting::vesting_balance(&4), Some(512));
+			System::set_block_number(11);
+			assert_eq!(Vesting::vesting_balance(&4), Some(448));
+		});
+}
+
+#[test]
+fn permissioned_vest_other_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			System::set_block_number(2);
+			crate::mock::PERMISSIONED_VEST_OTHER.with(|v| *v.borrow_mut() = true);
+			assert_noop!(Vesting::vest_other(Some(3).into(), 1), Error::<Test>::NotPermittedToVest);
+			// The target itself and operators may always vest.
+			assert_ok!(Vesting::vest_other(Some(1).into(), 1));
+			assert_ok!(Vesting::vest_other(Some(42).into(), 12));
+
+			assert_ok!(Vesting::approve_vester(Some(1).into(), 3));
+			System::assert_last_event(
+				crate::Event::<Test>::VesterApproved { account: 1, vester: 3 }.into(),
+			);
+			System::set_block_number(3);
+			assert_ok!(Vesting::vest_other(Some(3).into(), 1));
+			assert_eq!(Vesting::vesting_balance(&1), Some(35));
+
+			assert_ok!(Vesting::revoke_vester(Some(1).into(), 3));
+			System::assert_last_event(
+				crate::Event::<Test>::VesterRevoked { account: 1, vester: 3 }.into(),
+			);
+			assert_noop!(Vesting::vest_other(Some(3).into(), 1), Error::<Test>::NotPermittedToVest);
+
+			// Anyone may vest for others by default.
+			crate::mock::PERMISSIONED_VEST_OTHER.with(|v| *v.borrow_mut() = false);
+			assert_ok!(Vesting::vest_other(Some(3).into(), 1));
+		});
+}
+
+#[test]
+fn pause_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+			Balances::make_free_balance_be(&3, 10_000);
+			assert_noop!(Vesting::pause(Some(1).into()), BadOrigin);
+			assert_ok!(Vesting::pause(RawOrigin::Root.into()));
+			System::assert_last_event(crate::Event::<Test>::SchedulesPaused.into());
+			assert_noop!(Vesting::vested_transfer(Some(3).into(), 4, schedule), Error::<Test>::Paused);
+			assert_noop!(
+				Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, schedule),
+				Error::<Test>::Paused,
+			);
+			// Vesting carries on.
+			System::set_block_number(2);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::vesting_balance(&1), Some(40));
+
+			assert_ok!(Vesting::unpause(RawOrigin::Root.into()));
+			System::assert_last_event(crate::Event::<Test>::SchedulesUnpaused.into());
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+		});
+}
+
+#[test]
+fn incoming_schedules_are_rate_limited() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			// Vests immediately, so that the target may receive another one.
+			let schedule = VestingInfo { locked: 512, per_block: 512, starting_block: 0 };
+			Balances::make_free_balance_be(&3, 10_000);
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+			assert_eq!(Vesting::incoming_schedules(&4), Some((0, 2)));
+			assert_noop!(
+				Vesting::vested_transfer(Some(3).into(), 4, schedule),
+				Error::<Test>::TooManyIncomingSchedules,
+			);
+			// Other accounts are unaffected.
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 5, schedule));
+
+			System::set_block_number(10);
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+			assert_eq!(Vesting::incoming_schedules(&4), Some((10, 1)));
+		});
+}
+
+#[test]
+fn schedule_deposit_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			crate::mock::SCHEDULE_DEPOSIT.with(|v| *v.borrow_mut() = 5);
+			Balances::make_free_balance_be(&3, 10_000);
+			let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 1 };
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+			assert_eq!(Balances::reserved_balance(&3), 5);
+			assert_eq!(Balances::free_balance(&3), 10_000 - 512 - 5);
+			assert_eq!(Vesting::schedule_deposit(&4), Some((3, 5)));
+
+			// The deposit is returned once the schedule completes.
+			System::set_block_number(10);
+			assert_ok!(Vesting::vest(Some(4).into()));
+			assert_eq!(Balances::reserved_balance(&3), 0);
+			assert_eq!(Vesting::schedule_deposit(&4), None);
+
+			// Only what is left of the deposit is returned, leaving funds reserved by other pallets.
+			use frame_support::traits::{NamedReservableCurrency, ReservableCurrency};
+			let schedule = VestingInfo { starting_block: 10, ..schedule };
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 5, schedule));
+			assert_ok!(Balances::reserve(&3, 50));
+			let id = crate::mock::VestingReserveId::get();
+			let _ = Balances::slash_reserved_named(&id, &3, 5);
+			System::set_block_number(20);
+			assert_ok!(Vesting::vest(Some(5).into()));
+			assert_eq!(Balances::reserved_balance(&3), 50);
+		});
+}
+
+#[test]
+fn min_vested_transfer_policy_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let set_policy = |policy| crate::mock::TRANSFER_POLICY.with(|v| *v.borrow_mut() = policy);
+			Balances::make_free_balance_be(&3, 10_000);
+			let exact = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+			set_policy(MinVestedTransferPolicy { inclusive: false, ..Default::default() });
+			assert_noop!(Vesting::vested_transfer(Some(3).into(), 4, exact), Error::<Test>::AmountLow);
+			set_policy(Default::default());
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, exact));
+
+			// Only 500 is still locked by block 10.
+			System::set_block_number(10);
+			let partly_vested = VestingInfo { locked: 600, per_block: 10, starting_block: 0 };
+			set_policy(MinVestedTransferPolicy { check_remaining: true, ..Default::default() });
+			assert_noop!(
+				Vesting::vested_transfer(Some(3).into(), 5, partly_vested),
+				Error::<Test>::AmountLow,
+			);
+			set_policy(Default::default());
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 5, partly_vested));
+
+			let small = VestingInfo { locked: 100, per_block: 10, starting_block: 10 };
+			assert_noop!(
+				Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 6, small),
+				Error::<Test>::AmountLow,
+			);
+			set_policy(MinVestedTransferPolicy { exempt_forced: true, ..Default::default() });
+			assert_noop!(Vesting::vested_transfer(Some(3).into(), 6, small), Error::<Test>::AmountLow);
+			assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 6, small));
+		});
+}
+
+#[test]
+fn max_total_vested_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.max_total_vested(Some(1_000))
+		.build()
+		.execute_with(|| {
+			Balances::make_free_balance_be(&3, 10_000);
+			let schedule = VestingInfo { locked: 1_001, per_block: 10, starting_block: 10 };
+			assert_noop!(
+				Vesting::vested_transfer(Some(3).into(), 4, schedule),
+				Error::<Test>::TooMuchVested,
+			);
+			assert_noop!(
+				<Vesting as VestingSchedule<u64>>::add_vesting_schedule(&3, 1_001, 10, 10),
+				Error::<Test>::TooMuchVested,
+			);
+			assert_ok!(Vesting::vested_transfer(
+				Some(3).into(),
+				4,
+				VestingInfo { locked: 1_000, ..schedule },
+			));
+		});
+}
+
+#[test]
+fn set_min_vested_transfer_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			assert_eq!(Vesting::min_vested_transfer(), 512);
+			assert_noop!(Vesting::set_min_vested_transfer(Some(1).into(), 1_000), BadOrigin);
+			assert_ok!(Vesting::set_min_vested_transfer(RawOrigin::Root.into(), 1_000));
+			System::assert_last_event(
+				crate::Event::<Test>::MinVestedTransferSet { amount: 1_000 }.into(),
+			);
+
+			Balances::make_free_balance_be(&3, 10_000);
+			let schedule = VestingInfo { locked: 999, per_block: 10, starting_block: 10 };
+			assert_noop!(Vesting::vested_transfer(Some(3).into(), 4, schedule), Error::<Test>::AmountLow);
+			assert_ok!(Vesting::vested_transfer(
+				Some(3).into(),
+				4,
+				VestingInfo { locked: 1_000, ..schedule },
+			));
+		});
+}
+
+#[test]
+fn genesis_schedules_work() {
+	let schedule = VestingInfo { locked: 100, per_block: 7, starting_block: 5 };
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.schedules(vec![(3, schedule)])
+		.build()
+		.execute_with(|| {
+			assert_eq!(Vesting::vesting(&3), Some(schedule));
+			assert_eq!(Vesting::vesting_balance(&3), Some(100));
+			assert_eq!(Vesting::lifetime_totals(&3).locked(), 100);
+		});
+}
+
+#[test]
+#[should_panic(expected = "Accounts may only have one vesting schedule")]
+fn genesis_schedules_must_not_clash() {
+	let schedule = VestingInfo { locked: 10, per_block: 1, starting_block: 5 };
+	ExtBuilder::default().schedules(vec![(1, schedule)]).build();
+}
+
+#[test]
+fn genesis_endowed_schedules_work() {
+	let schedule = VestingInfo { locked: 100, per_block: 10, starting_block: 5 };
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.endowed_schedules(vec![(5, schedule)])
+		.build()
+		.execute_with(|| {
+			assert_eq!(Balances::free_balance(&5), 100);
+			assert_eq!(Balances::total_issuance(), 1100 + 100);
+			assert_eq!(Vesting::vesting(&5), Some(schedule));
+			assert_eq!(Vesting::vesting_balance(&5), Some(100));
+		});
+}
+
+#[test]
+#[should_panic(expected = "[(1, \"Accounts may only have one vesting schedule\"), \
+	(5, \"Currencies must be init'd before vesting\"), \
+	(6, \"Vesting schedules must lock funds and vest some every block\")]")]
+fn genesis_reports_all_invalid_entries() {
+	let schedule = VestingInfo { locked: 10, per_block: 1, starting_block: 5 };
+	let no_vesting = VestingInfo { locked: 10, per_block: 0, starting_block: 5 };
+	ExtBuilder::default()
+		.schedules(vec![(1, schedule), (5, schedule)])
+		.endowed_schedules(vec![(6, no_vesting)])
+		.build();
+}
+
+#[test]
+fn genesis_can_skip_invalid_entries() {
+	let schedule = VestingInfo { locked: 10, per_block: 1, starting_block: 5 };
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.schedules(vec![(1, schedule), (5, schedule), (3, schedule)])
+		.skip_invalid(true)
+		.build()
+		.execute_with(|| {
+			assert_eq!(Vesting::vesting(&1).unwrap().locked, 50);
+			assert_eq!(Vesting::vesting(&5), None);
+			assert_eq!(Vesting::vesting(&3), Some(schedule));
+		});
+}
+
+#[test]
+fn genesis_applies_max_total_vested() {
+	// Account 2 locks 200 under its genesis schedule.
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.max_total_vested(Some(150))
+		.skip_invalid(true)
+		.build()
+		.execute_with(|| {
+			assert_eq!(Vesting::vesting(&2), None);
+			assert_eq!(Vesting::vesting_balance(&1), Some(45));
+		});
+}
+
+/// Store the schedules in the layout they had before `V4`, for testing the migrations from it.
+fn store_unversioned_schedules() {
+	crate::migrations::deprecated::Vesting::<Test>::translate::<VersionedVestingInfo<u64, u64>, _>(
+		|_, schedule| Some(schedule.into_latest()),
+	);
+}
+
+#[test]
+fn v1_migration_initialises_lifetime_totals() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		assert_eq!(crate::StorageVersion::<Test>::get(), crate::Releases::V4);
+		// Pretend account 1 was vesting before the totals were introduced.
+		crate::StorageVersion::<Test>::put(crate::Releases::V0);
+		store_unversioned_schedules();
+		LifetimeTotals::<Test>::remove(&1);
+		System::set_block_number(4);
+
+		assert_ok!(crate::migrations::v1::pre_migrate::<Test>());
+		Vesting::on_runtime_upgrade();
+		assert_ok!(crate::migrations::v1::post_migrate::<Test>());
+
+		assert_eq!(crate::StorageVersion::<Test>::get(), crate::Releases::V4);
+		let totals = Vesting::lifetime_totals(&1);
+		assert_eq!(totals.schedules, 1);
+		assert_eq!(totals.received, 50);
+		assert_eq!(totals.vested, 20);
+		assert_eq!(totals.locked(), Vesting::vesting_balance(&1).unwrap());
+		// Accounts with totals already are left alone.
+		assert_eq!(Vesting::lifetime_totals(&2).received, 200);
+	});
+}
+
+#[test]
+fn v1_migration_unlocks_unclaimed_vested_funds() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		crate::StorageVersion::<Test>::put(crate::Releases::V0);
+		store_unversioned_schedules();
+		LifetimeTotals::<Test>::remove(&1);
+		// Account 1 has vested 30 by block 6, but hasn't called `vest` to claim any of it.
+		System::set_block_number(6);
+		assert_eq!(Balances::locks(&1)[0].amount, 50);
+
+		Vesting::on_runtime_upgrade();
+
+		let totals = Vesting::lifetime_totals(&1);
+		assert_eq!(totals.locked(), 20);
+		assert_eq!(Balances::locks(&1)[0].amount, totals.locked());
+		assert_ok!(Balances::transfer(Some(1).into(), 3, 80));
+		assert_ok!(Vesting::vest(Some(1).into()));
+		assert_eq!(Balances::locks(&1)[0].amount, 20);
+	});
+}
+
+#[test]
+fn v2_migration_corrects_invalid_schedules() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		crate::StorageVersion::<Test>::put(crate::Releases::V1);
+		store_unversioned_schedules();
+		// Legacy schedules which would never vest, or lock nothing.
+		let stuck = VestingInfo { locked: 100, per_block: 0, starting_block: 10 };
+		let empty = VestingInfo { locked: 0, per_block: 0, starting_block: 10 };
+		crate::migrations::deprecated::Vesting::<Test>::insert(&3, stuck);
+		crate::migrations::deprecated::Vesting::<Test>::insert(&4, empty);
+		Balances::set_lock(VESTING_ID, &4, 0, WithdrawReasons::all());
+
+		assert_ok!(crate::migrations::v2::pre_migrate::<Test>());
+		Vesting::on_runtime_upgrade();
+		assert_ok!(crate::migrations::v2::post_migrate::<Test>());
+
+		assert_eq!(crate::StorageVersion::<Test>::get(), crate::Releases::V4);
+		assert_eq!(Vesting::vesting(&3), Some(VestingInfo { per_block: 1, ..stuck }));
+		assert_eq!(Vesting::vesting(&4), None);
+		assert!(Balances::locks(&4).is_empty());
+		// Valid schedules are untouched.
+		assert_eq!(Vesting::vesting(&1).unwrap().per_block, 5);
+	});
+}
+
+#[test]
+fn genesis_fractional_vesting_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.fractional_vesting(vec![
+			FractionalGenesisVesting { who: 3, begin: 10, length: 20, locked: Perbill::from_percent(30) },
+			FractionalGenesisVesting { who: 4, begin: 10, length: 7, locked: Perbill::one() },
+		])
+		.build()
+		.execute_with(|| {
+			// 30% of 300 locked over 20 blocks.
+			let schedule = VestingInfo { locked: 90, per_block: 4, starting_block: 10 };
+			assert_eq!(Vesting::vesting(&3), Some(schedule));
+			// Everything locked, with no rounding of the liquid amount.
+			assert_eq!(Vesting::vesting(&4).unwrap().locked, 400);
+			assert_eq!(Vesting::vesting_balance(&4), Some(400));
+		});
+}
+
+#[test]
+fn genesis_vesting_is_self_describing() {
+	let entry = GenesisVesting { who: 1u64, begin: 0u64, length: 10u64, liquid: 50u64 };
+	let json = r#"{"who":1,"begin":0,"length":10,"liquid":50}"#;
+	assert_eq!(serde_json::to_string(&entry).unwrap(), json);
+	assert_eq!(serde_json::from_str::<GenesisVesting<u64, u64, u64>>(json).unwrap(), entry);
+	// Misspelt fields are rejected rather than silently defaulted.
+	assert!(serde_json::from_str::<GenesisVesting<u64, u64, u64>>(
+		r#"{"who":1,"begin":0,"lenght":10,"liquid":50}"#
+	).is_err());
+}
+
+#[test]
+fn genesis_vesting_until_works() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.vesting_until(vec![GenesisVestingUntil { who: 3, begin: 10, end: 17, liquid: 0 }])
+		.build()
+		.execute_with(|| {
+			// 300 over 7 blocks rounds up to 43 a block.
+			let schedule = VestingInfo { locked: 300, per_block: 43, starting_block: 10 };
+			assert_eq!(Vesting::vesting(&3), Some(schedule));
+			System::set_block_number(16);
+			assert_eq!(Vesting::vesting_balance(&3), Some(42));
+			// Fully vested exactly at the end block.
+			System::set_block_number(17);
+			assert_eq!(Vesting::vesting_balance(&3), Some(0));
+		});
+}
+
+#[test]
+#[should_panic(expected = "Vesting must end after it begins")]
+fn genesis_vesting_until_must_end_after_beginning() {
+	ExtBuilder::default()
+		.vesting_until(vec![GenesisVestingUntil { who: 3, begin: 10, end: 10, liquid: 0 }])
+		.build();
+}
+
+#[test]
+fn unlockable_at_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		// 50 of 100 locked from block 0, unlocking 5 a block.
+		assert_eq!(Vesting::unlockable_at(&1, 1), 55);
+		assert_eq!(Vesting::unlockable_at(&1, 6), 80);
+		assert_eq!(Vesting::unlockable_at(&1, 20), 100);
+		// Capped by the free balance.
+		assert_ok!(Balances::transfer(Some(1).into(), 3, 50));
+		assert_eq!(Vesting::unlockable_at(&1, 1), 5);
+		assert_eq!(Vesting::unlockable_at(&1, 6), 30);
+		assert_eq!(Vesting::unlockable_at(&1, 20), 50);
+		// Accounts which aren't vesting.
+		assert_eq!(Vesting::unlockable_at(&3, 1), 350);
+	});
+}
+
+#[test]
+fn next_unlock_block_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		assert_eq!(Vesting::next_unlock_block(&1), Some((2, 5)));
+		// Before the schedule starts.
+		assert_eq!(Vesting::next_unlock_block(&2), Some((11, 10)));
+		assert_eq!(Vesting::next_unlock_block(&3), None);
+
+		// At half speed from block 1, the vesting clock reaches the start of account 2's
+		// schedule at block 19, and only moves on by a whole block every other block.
+		let half = FixedU128::saturating_from_rational(1, 2);
+		assert_ok!(Vesting::set_unlock_multiplier(RawOrigin::Root.into(), half));
+		System::set_block_number(12);
+		assert_eq!(Vesting::next_unlock_block(&2), Some((21, 10)));
+		System::set_block_number(21);
+		assert_eq!(Vesting::next_unlock_block(&2), Some((23, 10)));
+		System::set_block_number(22);
+		assert_eq!(Vesting::next_unlock_block(&2), Some((23, 10)));
+
+		// Nothing further once fully vested.
+		assert_eq!(Vesting::next_unlock_block(&1), None);
+	});
+}
+
+#[test]
+fn vesting_timeline_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		assert_eq!(Vesting::vesting_timeline(&1, 100), vec![(1, 45), (10, 0)]);
+		assert_eq!(Vesting::vesting_timeline(&1, 5), vec![(1, 45), (5, 25)]);
+		// 200 unlocking 10 a block from block 10.
+		assert_eq!(Vesting::vesting_timeline(&2, 100), vec![(1, 200), (10, 200), (30, 0)]);
+		assert_eq!(Vesting::vesting_timeline(&2, 5), vec![(1, 200), (5, 200)]);
+		assert_eq!(Vesting::vesting_timeline(&2, 0), vec![]);
+		assert_eq!(Vesting::vesting_timeline(&3, 100), vec![]);
+
+		// At half speed the schedule takes twice as long to start and to finish.
+		let half = FixedU128::saturating_from_rational(1, 2);
+		assert_ok!(Vesting::set_unlock_multiplier(RawOrigin::Root.into(), half));
+		assert_eq!(Vesting::vesting_timeline(&2, 100), vec![(1, 200), (19, 200), (59, 0)]);
+	});
+}
+
+#[test]
+fn total_unvested_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		// 45 of account 1, 200 of account 2 and 50 of account 12.
+		assert_eq!(Vesting::total_unvested(None, 10), (295, None));
+		System::set_block_number(20);
+		assert_eq!(Vesting::total_unvested(None, 10).0, 100 + 30);
+		// Account 12 unlocks 2 a block, so is still vesting.
+		System::set_block_number(30);
+		assert_eq!(Vesting::total_unvested(None, 10).0, 10);
+		System::set_block_number(40);
+		assert_eq!(Vesting::total_unvested(None, 10), (0, None));
+	});
+}
+
+#[test]
+fn total_unvested_paginates() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let (first, cursor) = Vesting::total_unvested(None, 2);
+		assert!(cursor.is_some());
+		let (rest, cursor) = Vesting::total_unvested(cursor, 2);
+		assert_eq!(cursor, None);
+		assert_eq!(first + rest, 295);
+		// Each account is counted once.
+		let mut total = 0;
+		let mut cursor = None;
+		loop {
+			let (page, next) = Vesting::total_unvested(cursor, 1);
+			total += page;
+			match next {
+				Some(_) => cursor = next,
+				None => break,
+			}
+		}
+		assert_eq!(total, 295);
+	});
+}
+
+#[test]
+fn vesting_accounts_are_paged() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let first = Vesting::vesting_accounts(None, 2);
+		assert_eq!(first.len(), 2);
+		let rest = Vesting::vesting_accounts(Some(first[1].0), 2);
+		assert_eq!(rest.len(), 1);
+		assert!(Vesting::vesting_accounts(Some(rest[0].0), 2).is_empty());
+		assert!(Vesting::vesting_accounts(None, 0).is_empty());
+
+		let mut accounts: Vec<_> = first.into_iter().chain(rest).collect();
+		accounts.sort_by_key(|(who, _)| *who);
+		assert_eq!(accounts.iter().map(|(who, _)| *who).collect::<Vec<_>>(), vec![1, 2, 12]);
+		assert_eq!(accounts[0].1, VestingSummary {
+			schedule: Vesting::vesting(&1).unwrap(),
+			unvested: 45,
+			vestable: 5,
+		});
+	});
+}
+
+#[test]
+fn schedule_status_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		System::set_block_number(4);
+		assert_eq!(Vesting::schedule_status(&1), Some(ScheduleStatus {
+			schedule: Vesting::vesting(&1).unwrap(),
+			ending_block: Some(10),
+			vested: 20,
+			remaining: 30,
+			plan: None,
+			revocable: false,
+			created_at: Some(0),
+		}));
+		assert_eq!(Vesting::schedule_status(&3), None);
+
+		let plan = VestingPlan { cliff: 0, duration: 16, revocable: true };
+		assert_ok!(Vesting::create_vesting_plan(RawOrigin::Root.into(), plan));
+		Balances::make_free_balance_be(&4, 10_000);
+		assert_ok!(Vesting::vested_transfer_with_plan(Some(4).into(), 5, 0, 512));
+		System::set_block_number(8);
+		let status = Vesting::schedule_status(&5).unwrap();
+		assert_eq!(status.ending_block, Some(20));
+		assert_eq!((status.vested, status.remaining), (128, 384));
+		assert_eq!((status.plan, status.revocable), (Some(0), true));
+		assert_eq!(status.created_at, Some(4));
+	});
+}
+
+#[test]
+fn vesting_summaries_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		assert_eq!(
+			Vesting::vesting_summaries(&[3, 12, 1]),
+			vec![None, Vesting::vesting_summary(&12), Vesting::vesting_summary(&1)],
+		);
+		assert_eq!(Vesting::vesting_summary(&12).unwrap().unvested, 50);
+		assert!(Vesting::vesting_summaries(&[]).is_empty());
+	});
+}
+
+#[test]
+fn vesting_id_is_in_metadata() {
+	use frame_support::dispatch::DecodeDifferent;
+
+	let constant = Vesting::module_constants_metadata()
+		.iter()
+		.find(|c| c.name == DecodeDifferent::Encode("VestingId"))
+		.expect("VestingId is a constant");
+	match &constant.value {
+		DecodeDifferent::Encode(getter) => assert_eq!(getter.0.default_byte(), b"vesting ".to_vec()),
+		DecodeDifferent::Decoded(_) => unreachable!(),
+	}
+}
+
+#[test]
+fn aggregate_schedule_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		assert_eq!(Vesting::aggregate_schedule(&1), Some((45, 10)));
+		assert_eq!(Vesting::aggregate_schedule(&2), Some((200, 30)));
+		assert_eq!(Vesting::aggregate_schedule(&3), None);
+		assert_ok!(Vesting::freeze_progression(RawOrigin::Root.into()));
+		assert_eq!(Vesting::aggregate_schedule(&2), None);
+	});
+}
+
+#[test]
+fn funded_schedules_are_indexed() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		Balances::make_free_balance_be(&3, 10_000);
+		let short = VestingInfo { locked: 512, per_block: 128, starting_block: 1 };
+		let long = VestingInfo { locked: 512, per_block: 1, starting_block: 1 };
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 5, short));
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 6, long));
+		assert_eq!(Vesting::funded_by(&5), Some(3));
+		let mut funded = Vesting::funded_schedules(&3);
+		funded.sort_by_key(|(who, _)| *who);
+		assert_eq!(funded, vec![(5, short), (6, long)]);
+		// Genesis schedules have no funder.
+		assert_eq!(Vesting::funded_by(&1), None);
+
+		// Completed schedules drop out of the index.
+		System::set_block_number(10);
+		assert_ok!(Vesting::vest(Some(5).into()));
+		assert_eq!(Vesting::funded_by(&5), None);
+		assert_eq!(Vesting::funded_schedules(&3), vec![(6, long)]);
+	});
+}
+
+#[test]
+fn schedules_are_indexed_by_end() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let ending_at = |n: u64| {
+			let mut accounts: Vec<u64> = crate::SchedulesEndingAt::<Test>::iter_key_prefix(n).collect();
+			accounts.sort();
+			accounts
+		};
+		// Account 12 vests 50 at 2 a block from block 10.
+		assert_eq!(ending_at(10), vec![1]);
+		assert_eq!(ending_at(30), vec![2]);
+		assert_eq!(ending_at(35), vec![12]);
+
+		Balances::make_free_balance_be(&3, 10_000);
+		let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 2 };
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 5, schedule));
+		assert_eq!(ending_at(10), vec![1, 5]);
+
+		// Slashing may move the schedule's end, as 170 now vests at 8 a block.
+		assert_eq!(Vesting::on_slash(&2, 30), 30);
+		assert!(ending_at(30).is_empty());
+		assert_eq!(ending_at(32), vec![2]);
+
+		// Completed schedules are removed from the index.
+		System::set_block_number(10);
+		assert_ok!(Vesting::vest(Some(1).into()));
+		assert_eq!(ending_at(10), vec![5]);
+	});
+}
+
+#[test]
+fn v3_migration_indexes_schedules() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		crate::StorageVersion::<Test>::put(crate::Releases::V2);
+		store_unversioned_schedules();
+		crate::SchedulesEndingAt::<Test>::remove_all(None);
+
+		assert_ok!(crate::migrations::v3::pre_migrate::<Test>());
+		Vesting::on_runtime_upgrade();
+		assert_ok!(crate::migrations::v3::post_migrate::<Test>());
+
+		assert_eq!(crate::StorageVersion::<Test>::get(), crate::Releases::V4);
+		assert!(crate::SchedulesEndingAt::<Test>::contains_key(30, 2));
+	});
+}
+
+#[test]
+fn vest_completing_schedule_is_free() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		// Account 1's schedule is still vesting, so the call is charged.
+		System::set_block_number(5);
+		let info = Vesting::vest(Some(1).into()).unwrap();
+		assert_eq!(info.pays_fee, Pays::Yes);
+
+		// The call which completes the schedule is free.
+		System::set_block_number(10);
+		let info = Vesting::vest(Some(1).into()).unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+		assert_eq!(Vesting::vesting(&1), None);
+	});
+}
+
+#[test]
+fn vest_other_completing_schedule_is_free() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		System::set_block_number(5);
+		let info = Vesting::vest_other(Some(3).into(), 1).unwrap();
+		assert_eq!(info.pays_fee, Pays::Yes);
+
+		System::set_block_number(10);
+		let info = Vesting::vest_other(Some(3).into(), 1).unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+		assert_eq!(Vesting::vesting(&1), None);
+	});
+}
+
+#[test]
+fn fully_locked_account_can_pay_vest_fee() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		// Account 2's whole balance is locked.
+		assert_eq!(Vesting::vesting_balance(&2), Some(200));
+		assert_noop!(
+			Balances::transfer(Some(2).into(), 3, 10),
+			pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+		);
+
+		// The lock doesn't prevent withdrawing a transaction fee.
+		assert_ok!(<Balances as Currency<_>>::withdraw(
+			&2,
+			10,
+			WithdrawReasons::TRANSACTION_PAYMENT,
+			ExistenceRequirement::KeepAlive,
+		));
+		assert_ok!(Vesting::vest(Some(2).into()));
+	});
+}
+
+#[test]
+fn export_and_import_schedule_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		System::set_block_number(15);
+		assert_noop!(Vesting::export_schedule(Some(1).into(), 2), BadOrigin);
+		assert_noop!(
+			Vesting::export_schedule(RawOrigin::Root.into(), 3),
+			Error::<Test>::NotVesting,
+		);
+
+		// Account 2 has vested 50 of 200, so 150 remain to vest from now on.
+		assert_ok!(Vesting::export_schedule(RawOrigin::Root.into(), 2));
+		let remaining = VestingInfo { locked: 150, per_block: 10, starting_block: 15 };
+		System::assert_last_event(
+			crate::Event::<Test>::VestingExported { account: 2, remaining }.into(),
+		);
+		assert_eq!(Vesting::vesting(&2), None);
+		assert_ok!(Balances::transfer(Some(2).into(), 3, 200));
+
+		// Importing requires the funds to be held already.
+		let too_much = VestingInfo { locked: 500, ..remaining };
+		assert_noop!(
+			Vesting::import_schedule(RawOrigin::Root.into(), 4, too_much),
+			Error::<Test>::BalanceTooLow,
+		);
+		assert_noop!(
+			Vesting::import_schedule(RawOrigin::Root.into(), 1, remaining),
+			Error::<Test>::ExistingVestingSchedule,
+		);
+
+		assert_ok!(Vesting::import_schedule(RawOrigin::Root.into(), 4, remaining));
+		System::assert_last_event(
+			crate::Event::<Test>::VestingImported { account: 4, schedule: remaining }.into(),
+		);
+		assert_eq!(Vesting::vesting_balance(&4), Some(150));
+		System::set_block_number(20);
+		assert_eq!(Vesting::vesting_balance(&4), Some(100));
+	});
+}
+
+#[test]
+fn vested_transfer_to_derivative_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let derivative = Vesting::derivative_account_id(&4, 0).unwrap();
+		assert_ne!(derivative, 4);
+		assert_ne!(Some(derivative), Vesting::derivative_account_id(&4, 1));
+
+		let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+		Balances::make_free_balance_be(&3, 10_000);
+		assert_ok!(Vesting::vested_transfer_to_derivative(Some(3).into(), 4, 0, schedule));
+		assert_eq!(Vesting::vesting(&derivative), Some(schedule));
+		assert_eq!(Balances::free_balance(&derivative), 512);
+		assert_eq!(Vesting::vesting(&4), None);
+	});
+}
+
+#[test]
+fn vested_transfer_to_sovereign_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let sovereign = Vesting::sovereign_account_id(&7).unwrap();
+		let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+		Balances::make_free_balance_be(&3, 10_000);
+		assert_ok!(Vesting::vested_transfer_to_sovereign(Some(3).into(), 7, schedule));
+		assert_eq!(Vesting::vesting(&sovereign), Some(schedule));
+		assert_eq!(Balances::free_balance(&sovereign), 512);
+
+		// Locations without a sovereign account can't be targeted.
+		assert_eq!(Vesting::sovereign_account_id(&0), None);
+		assert_noop!(
+			Vesting::vested_transfer_to_sovereign(Some(3).into(), 0, schedule),
+			Error::<Test>::NoDerivedAccount,
+		);
+	});
+}
+
+#[test]
+fn shadow_asset_mirrors_locked_funds() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		assert_ok!(Assets::force_create(RawOrigin::Root.into(), 0, 1, true, 1));
+
+		let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+		Balances::make_free_balance_be(&3, 10_000);
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 5, schedule));
+		assert_eq!(Assets::balance(0, 5), 512);
+
+		System::set_block_number(12);
+		assert_ok!(Vesting::vest(Some(5).into()));
+		assert_eq!(Assets::balance(0, 5), 384);
+
+		// The mirrored balance is burnt once the schedule is removed.
+		assert_ok!(Vesting::export_schedule(RawOrigin::Root.into(), 5));
+		assert_eq!(Assets::balance(0, 5), 0);
+	});
+}
+
+#[test]
+fn mock_constants_can_be_set_per_test() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.min_vested_transfer(100)
+		.max_incoming_schedules(1)
+		.build()
+		.execute_with(|| {
+			assert_eq!(<Test as Config>::MinVestedTransfer::get(), 100);
+			let schedule = VestingInfo { locked: 100, per_block: 10, starting_block: 10 };
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 5, schedule));
+
+			// The second transfer in the period is over the lowered limit.
+			assert_ok!(Vesting::export_schedule(RawOrigin::Root.into(), 5));
+			assert_noop!(
+				Vesting::vested_transfer(Some(3).into(), 5, schedule),
+				Error::<Test>::TooManyIncomingSchedules,
+			);
+		});
+}
+
+#[test]
+fn test_utils_work() {
+	use crate::test_utils::*;
+
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let schedule = VestingInfo { locked: 100, per_block: 10, starting_block: 5 };
+		assert_ok!(seed_schedule::<Test>(&7, schedule));
+		assert_locked::<Test>(&7, Some(100));
+		assert_transferable::<Test>(&7, 10);
+
+		assert_ok!(vest_at::<Test>(&7, 8));
+		assert_locked::<Test>(&7, Some(70));
+		assert_transferable::<Test>(&7, 40);
+
+		assert_ok!(vest_at::<Test>(&7, 15));
+		assert_locked::<Test>(&7, None);
+		assert_transferable::<Test>(&7, 110);
+	});
+}
+
+#[test]
+fn vest_matured_works() {
+	use frame_support::unsigned::ValidateUnsigned;
+	use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource};
+
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.build()
+		.execute_with(|| {
+			let call = crate::Call::vest_matured(1);
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &call),
+				InvalidTransaction::Stale.into(),
+			);
+			assert_noop!(
+				Vesting::vest_matured(RawOrigin::None.into(), 1),
+				Error::<Test>::NotMatured,
+			);
+
+			System::set_block_number(10);
+			assert_ok!(Vesting::validate_unsigned(TransactionSource::External, &call));
+			assert_noop!(Vesting::vest_matured(Some(1).into(), 1), BadOrigin);
+			assert_ok!(Vesting::vest_matured(RawOrigin::None.into(), 1));
+			assert_eq!(Vesting::vesting(&1), None);
+
+			// Completed schedules can't be completed again.
+			assert_eq!(
+				Vesting::validate_unsigned(TransactionSource::External, &call),
+				InvalidTransaction::Stale.into(),
+			);
+		});
+}
+
+#[test]
+fn offchain_worker_submits_vest_matured() {
+	use codec::Decode;
+	use frame_support::traits::OffchainWorker;
+	use sp_core::offchain::{testing::TestTransactionPoolExt, TransactionPoolExt};
+
+	let mut ext = ExtBuilder::default().existential_deposit(10).build();
+	let (pool, state) = TestTransactionPoolExt::new();
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.execute_with(|| {
+		// Nothing matures at block 9.
+		<Vesting as OffchainWorker<u64>>::offchain_worker(9);
+		assert!(state.read().transactions.is_empty());
+
+		<Vesting as OffchainWorker<u64>>::offchain_worker(10);
+		let txs = &state.read().transactions;
+		assert_eq!(txs.len(), 1);
+		let tx = frame_system::mocking::MockUncheckedExtrinsic::<Test>::decode(&mut &txs[0][..])
+			.unwrap();
+		assert_eq!(tx.signature, None);
+		assert_eq!(tx.function, mock::Call::Vesting(crate::Call::vest_matured(1)));
+	});
+}
+
+#[test]
+fn force_apply_vesting_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let schedule = VestingInfo { locked: 300, per_block: 30, starting_block: 5 };
+		assert_noop!(Vesting::force_apply_vesting(Some(3).into(), 3, schedule), BadOrigin);
+		let too_much = VestingInfo { locked: 301, ..schedule };
+		assert_noop!(
+			Vesting::force_apply_vesting(RawOrigin::Root.into(), 3, too_much),
+			Error::<Test>::BalanceTooLow,
+		);
+		assert_noop!(
+			Vesting::force_apply_vesting(RawOrigin::Root.into(), 2, schedule),
+			Error::<Test>::ExistingVestingSchedule,
+		);
+
+		// The whole balance of account 3 is locked in place, without moving any funds.
+		assert_ok!(Vesting::force_apply_vesting(RawOrigin::Root.into(), 3, schedule));
+		System::assert_last_event(
+			crate::Event::<Test>::VestingApplied { account: 3, schedule }.into(),
+		);
+		assert_eq!(Balances::free_balance(&3), 300);
+		assert_eq!(Vesting::vesting_balance(&3), Some(300));
+		assert_noop!(
+			Balances::transfer(Some(3).into(), 4, 10),
+			pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+		);
+
+		System::set_block_number(7);
+		assert_ok!(Vesting::vest(Some(3).into()));
+		assert_eq!(Vesting::vesting_balance(&3), Some(240));
+		assert_ok!(Balances::transfer(Some(3).into(), 4, 60));
+	});
+}
+
+#[test]
+fn vest_own_balance_works() {
+	ExtBuilder::default().existential_deposit(10).min_vested_transfer(100).build().execute_with(|| {
+		let schedule = VestingInfo { locked: 300, per_block: 10, starting_block: 10 };
+		assert_noop!(
+			Vesting::vest_own_balance(Some(4).into(), VestingInfo { locked: 99, ..schedule }),
+			Error::<Test>::AmountLow,
+		);
+		assert_noop!(
+			Vesting::vest_own_balance(Some(4).into(), VestingInfo { locked: 401, ..schedule }),
+			Error::<Test>::BalanceTooLow,
+		);
+		assert_noop!(
+			Vesting::vest_own_balance(Some(2).into(), schedule),
+			Error::<Test>::ExistingVestingSchedule,
+		);
+
+		assert_ok!(Vesting::vest_own_balance(Some(4).into(), schedule));
+		System::assert_last_event(
+			crate::Event::<Test>::VestingSelfImposed { account: 4, schedule }.into(),
+		);
+		assert!(SelfImposed::<Test>::contains_key(&4));
+		assert_eq!(Vesting::vesting_balance(&4), Some(300));
+		assert_ok!(Balances::transfer(Some(4).into(), 3, 100));
+		assert_noop!(
+			Balances::transfer(Some(4).into(), 3, 1),
+			pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+		);
+
+		// The mark goes with the schedule.
+		System::set_block_number(40);
+		assert_ok!(Vesting::vest(Some(4).into()));
+		assert_eq!(Vesting::vesting(&4), None);
+		assert!(!SelfImposed::<Test>::contains_key(&4));
+	});
+}
+
+#[test]
+fn exit_early_works() {
+	ExtBuilder::default().existential_deposit(10).min_vested_transfer(100).build().execute_with(|| {
+		assert_noop!(Vesting::exit_early(Some(3).into()), Error::<Test>::NotVesting);
+		assert_noop!(Vesting::exit_early(Some(2).into()), Error::<Test>::NotSelfImposed);
+
+		let schedule = VestingInfo { locked: 300, per_block: 10, starting_block: 10 };
+		assert_ok!(Vesting::vest_own_balance(Some(4).into(), schedule));
+
+		// 200 is still unvested, of which 10% is forfeited and burned.
+		System::set_block_number(20);
+		let issuance = Balances::total_issuance();
+		assert_ok!(Vesting::exit_early(Some(4).into()));
+		System::assert_last_event(
+			crate::Event::<Test>::VestingExitedEarly { account: 4, penalty: 20 }.into(),
+		);
+		assert_eq!(Balances::total_issuance(), issuance - 20);
+		assert_eq!(Vesting::vesting(&4), None);
+		assert!(!SelfImposed::<Test>::contains_key(&4));
+		assert_ok!(Balances::transfer(Some(4).into(), 3, 380));
+	});
+}
+
+#[test]
+fn v4_migration_versions_schedules() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		crate::StorageVersion::<Test>::put(crate::Releases::V3);
+		store_unversioned_schedules();
+		assert_eq!(Vesting::vesting(&1), None);
+
+		assert_ok!(crate::migrations::v4::pre_migrate::<Test>());
+		Vesting::on_runtime_upgrade();
+		assert_ok!(crate::migrations::v4::post_migrate::<Test>());
+
+		assert_eq!(crate::StorageVersion::<Test>::get(), crate::Releases::V4);
+		let schedule = VestingInfo { locked: 50, per_block: 5, starting_block: 0 };
+		assert_eq!(crate::Vesting::<Test>::get(&1), Some(VersionedVestingInfo::V1(schedule)));
+		assert_eq!(Vesting::vesting_balance(&2), Some(200));
+	});
+}
+
+#[test]
+fn vested_transfers_can_be_prevalidated() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+		assert_eq!(Vesting::remaining_schedule_slots(&1), 0);
+		assert_eq!(Vesting::remaining_schedule_slots(&3), 1);
+		assert_ok!(Vesting::validate_vested_transfer(&3, schedule));
+		assert_eq!(
+			Vesting::validate_vested_transfer(&1, schedule),
+			Err(Error::<Test>::ExistingVestingSchedule.into()),
+		);
+		assert_eq!(
+			Vesting::validate_vested_transfer(&3, VestingInfo { locked: 511, ..schedule }),
+			Err(Error::<Test>::AmountLow.into()),
+		);
+
+		mock::MaxIncomingSchedules::set(0);
+		assert_eq!(Vesting::remaining_schedule_slots(&3), 0);
+		assert_eq!(
+			Vesting::validate_vested_transfer(&3, schedule),
+			Err(Error::<Test>::TooManyIncomingSchedules.into()),
+		);
+	});
+}
+
+#[test]
+fn claim_vesting_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+		let other = VestingInfo { locked: 1024, ..schedule };
+		let leaf = |who, schedule| Vesting::claim_leaf(&who, &schedule);
+		let pair = |a, b| Vesting::claim_root_of(a, &[b]);
+		let node = pair(leaf(20, schedule), leaf(3, other));
+		let root = pair(node, leaf(21, schedule));
+
+		let proof = vec![leaf(3, other), leaf(21, schedule)];
+		assert_noop!(
+			Vesting::claim_vesting(Some(1).into(), 20, schedule, proof.clone()),
+			Error::<Test>::NoClaimsRoot,
+		);
+		assert_noop!(Vesting::set_claims_root(Some(1).into(), Some(root)), BadOrigin);
+		assert_ok!(Vesting::set_claims_root(RawOrigin::Root.into(), Some(root)));
+		System::assert_last_event(crate::Event::<Test>::ClaimsRootSet { root: Some(root) }.into());
+
+		// Allocations can't be altered, nor claimed with overlong proofs.
+		assert_noop!(
+			Vesting::claim_vesting(Some(1).into(), 20, other, proof.clone()),
+			Error::<Test>::InvalidClaimProof,
+		);
+		assert_noop!(
+			Vesting::claim_vesting(Some(1).into(), 20, schedule, vec![root; 17]),
+			Error::<Test>::ClaimProofTooLong,
+		);
+
+		// Anyone may claim on behalf of an account without funds.
+		assert_ok!(Vesting::claim_vesting(Some(1).into(), 20, schedule, proof.clone()));
+		System::assert_last_event(
+			crate::Event::<Test>::VestingClaimed { account: 20, schedule }.into(),
+		);
+		assert_eq!(Balances::free_balance(&20), 512);
+		assert_eq!(Vesting::vesting_balance(&20), Some(512));
+		assert_noop!(
+			Vesting::claim_vesting(Some(1).into(), 20, schedule, proof),
+			Error::<Test>::AlreadyClaimed,
+		);
+
+		assert_ok!(Vesting::claim_vesting(Some(1).into(), 21, schedule, vec![node]));
+		assert_ok!(Vesting::claim_vesting(
+			Some(1).into(),
+			3,
+			other,
+			vec![leaf(20, schedule), leaf(21, schedule)],
+		));
+		assert_eq!(Balances::free_balance(&3), 300 + 1024);
+	});
+}
+
+#[test]
+fn register_auto_vest_works() {
+	use frame_support::traits::{OnInitialize, VestingSchedule};
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		assert_noop!(Vesting::register_auto_vest(Some(3).into()), Error::<Test>::NotVesting);
+		assert_ok!(Vesting::register_auto_vest(Some(1).into()));
+		System::assert_last_event(
+			crate::Event::<Test>::AutoVestRegistered { account: 1, at: 10 }.into(),
+		);
+		assert_eq!(Vesting::auto_vest_at(&1), Some(10));
+		assert_noop!(Vesting::register_auto_vest(Some(1).into()), Error::<Test>::AlreadyRegistered);
+
+		// Account 12 vests at block 35 and account 2 at block 30.
+		crate::mock::MaxAutoVestsPerBlock::set(1);
+		assert_ok!(Vesting::register_auto_vest(Some(12).into()));
+		assert_ok!(Vesting::register_auto_vest(Some(2).into()));
+		assert_eq!(AutoVestQueue::<Test>::get(35).into_inner(), vec![12]);
+
+		// Registrations are dropped along with the schedule.
+		<Vesting as VestingSchedule<u64>>::remove_vesting_schedule(&12);
+		assert_eq!(Vesting::auto_vest_at(&12), None);
+		assert!(AutoVestQueue::<Test>::get(35).is_empty());
+
+		System::set_block_number(10);
+		<Vesting as OnInitialize<u64>>::on_initialize(10);
+		assert_eq!(Vesting::vesting(&1), None);
+		assert_eq!(Vesting::auto_vest_at(&1), None);
+		assert!(AutoVestQueue::<Test>::get(10).is_empty());
+		assert_ok!(Balances::transfer(Some(1).into(), 3, 90));
+		assert_eq!(Vesting::auto_vest_at(&2), Some(30));
+	});
+}
+
+#[test]
+fn validate_genesis_vesting_works() {
+	let schedule = VestingInfo { locked: 100, per_block: 10, starting_block: 0 };
+	let balances = vec![(1, 100), (2, 200), (3, 50), (2, 10)];
+	assert_eq!(
+		validate_genesis_vesting::<Test>(&[(1, schedule)], &balances[..3]),
+		Ok(()),
+	);
+
+	let entries = vec![
+		(1, schedule),
+		(1, schedule),
+		(2, VestingInfo { per_block: 0, ..schedule }),
+		(3, schedule),
+		(4, VestingInfo { starting_block: u64::max_value() - 5, ..schedule }),
+	];
+	assert_eq!(
+		validate_genesis_vesting::<Test>(&entries, &balances),
+		Err(vec![
+			(2, "Duplicate balances in genesis"),
+			(1, "Accounts may only have one vesting schedule"),
+			(2, "Vesting schedules must lock funds and vest some every block"),
+			(3, "Currencies must be init'd before vesting"),
+			(4, "Vesting must end before the maximum block number"),
+		]),
+	);
+
+	// The cap applies to each schedule, not to their sum.
+	crate::mock::MaxTotalVested::set(Some(150));
+	let entries = vec![(1, schedule), (2, VestingInfo { locked: 200, ..schedule }), (3, schedule)];
+	assert_eq!(
+		validate_genesis_vesting::<Test>(&entries, &[(1, 100), (2, 200), (3, 100)]),
+		Err(vec![(2, "Vesting schedules may not lock more than MaxTotalVested")]),
+	);
+}
+
+#[test]
+fn progression_freezes_work() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let end = |who| Vesting::schedule_status(&who).and_then(|status| status.ending_block);
+		assert_noop!(
+			Vesting::resume_progression(RawOrigin::Root.into()),
+			Error::<Test>::ProgressionNotFrozen,
+		);
+		System::set_block_number(3);
+		assert_noop!(Vesting::freeze_progression(Some(1).into()), BadOrigin);
+		assert_ok!(Vesting::freeze_progression(RawOrigin::Root.into()));
+		System::assert_last_event(crate::Event::<Test>::ProgressionFrozen { at: 3 }.into());
+		assert_noop!(
+			Vesting::freeze_progression(RawOrigin::Root.into()),
+			Error::<Test>::ProgressionAlreadyFrozen,
+		);
+
+		// Nothing more is unlocked while frozen, and account 1 won't finish vesting until resumed.
+		System::set_block_number(6);
+		assert_eq!(Vesting::vesting_balance(&1), Some(35));
+		assert_eq!(end(1), None);
+		assert_ok!(Vesting::resume_progression(RawOrigin::Root.into()));
+		System::assert_last_event(crate::Event::<Test>::ProgressionResumed { frozen_for: 3 }.into());
+
+		// Its end is pushed back by the blocks frozen, as is the start of account 2's schedule.
+		assert_eq!(end(1), Some(13));
+		assert_eq!(end(2), Some(33));
+		System::set_block_number(7);
+		assert_eq!(Vesting::vesting_balance(&1), Some(30));
+		System::set_block_number(13);
+		assert_eq!(Vesting::vesting_balance(&1), Some(0));
+
+		assert_eq!(Vesting::total_frozen(), 3);
+		assert_eq!(Vesting::progression_frozen_since(), None);
+
+		// Resumed freezes are only counted, so progression may be frozen any number of times.
+		for n in (14..44).step_by(3) {
+			System::set_block_number(n);
+			assert_ok!(Vesting::freeze_progression(RawOrigin::Root.into()));
+			assert_eq!(Vesting::progression_frozen_since(), Some(n));
+			System::set_block_number(n + 2);
+			assert_ok!(Vesting::resume_progression(RawOrigin::Root.into()));
+		}
+		assert_eq!(Vesting::total_frozen(), 23);
+		assert_eq!(end(2), Some(53));
+	});
+}
+
+#[test]
+fn withholding_works() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let rate = Perbill::from_percent(20);
+		assert_noop!(
+			Vesting::set_withholding(Some(3).into(), Some((4, rate))),
+			Error::<Test>::NotVesting,
+		);
+		assert_ok!(Vesting::set_withholding(Some(1).into(), Some((3, rate))));
+		System::assert_last_event(
+			crate::Event::<Test>::WithholdingSet { account: 1, withholding: Some((3, rate)) }.into(),
+		);
+
+		System::set_block_number(5);
+		assert_ok!(Vesting::vest(Some(1).into()));
+		let withheld = crate::Event::<Test>::VestingWithheld {
+			account: 1,
+			destination: 3,
+			withheld: 5,
+			kept: 20,
+		};
+		System::assert_has_event(withheld.into());
+		assert_eq!(Balances::free_balance(&1), 95);
+		assert_eq!(Balances::free_balance(&3), 305);
+		assert_eq!(Vesting::vesting_balance(&1), Some(25));
+
+		// The withholding is cleared once the schedule completes.
+		System::set_block_number(10);
+		assert_ok!(Vesting::vest(Some(1).into()));
+		assert_eq!(Balances::free_balance(&1), 90);
+		assert_eq!(Vesting::withholding(&1), None);
+	});
+}
+
+#[test]
+fn schedule_created_at_is_recorded() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		assert_eq!(Vesting::schedule_created_at(&1), Some(0));
+		assert_eq!(Vesting::schedule_created_at(&3), None);
+
+		System::set_block_number(3);
+		let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+		Balances::make_free_balance_be(&4, 10_000);
+		assert_ok!(Vesting::vested_transfer(Some(4).into(), 20, schedule));
+		assert_eq!(Vesting::schedule_created_at(&20), Some(3));
+
+		// Reducing the schedule keeps its creation block.
+		System::set_block_number(12);
+		Vesting::on_slash(&20, 128);
+		assert_ne!(Vesting::vesting(&20), Some(schedule));
+		assert_eq!(Vesting::schedule_created_at(&20), Some(3));
+
+		// Schedules stored before it was recorded have none.
+		crate::Vesting::<Test>::insert(&20, VersionedVestingInfo::V1(schedule));
+		assert_eq!(Vesting::schedule_created_at(&20), None);
+		assert_eq!(Vesting::vesting(&20), Some(schedule));
+	});
+}
+
+#[test]
+fn vested_transfer_with_metadata_works() {
+	use frame_support::BoundedVec;
+	use sp_std::convert::TryFrom;
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		let schedule = VestingInfo { locked: 512, per_block: 64, starting_block: 10 };
+		let metadata = BoundedVec::try_from(b"seed".to_vec()).unwrap();
+		Balances::make_free_balance_be(&4, 10_000);
+		assert_ok!(Vesting::vested_transfer_with_metadata(
+			Some(4).into(),
+			20,
+			schedule,
+			metadata,
+		));
+		let seed = b"seed".to_vec();
+		System::assert_last_event(
+			crate::Event::<Test>::VestingMetadataSet { account: 20, metadata: seed.clone() }.into(),
+		);
+		assert_eq!(Vesting::schedule_metadata(&20).map(|m| m.into_inner()), Some(seed));
+
+		// The metadata is cleared once the schedule completes.
+		System::set_block_number(18);
+		assert_ok!(Vesting::vest(Some(20).into()));
+		assert_eq!(Vesting::schedule_metadata(&20), None);
+
+		// It is never stored for schedules which have already ended.
+		let ended = VestingInfo { locked: 512, per_block: 512, starting_block: 0 };
+		let metadata = BoundedVec::try_from(b"seed".to_vec()).unwrap();
+		assert_ok!(Vesting::vested_transfer_with_metadata(Some(4).into(), 21, ended, metadata));
+		assert_eq!(Vesting::vesting(&21), None);
+		assert_eq!(Vesting::schedule_metadata(&21), None);
+		System::assert_last_event(crate::Event::<Test>::VestingCompleted { account: 21 }.into());
+	});
+}
@@ -15,26 +15,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Autogenerated weights for pallet_vesting
+//! Weights for pallet_vesting
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 3.0.0
-//! DATE: 2021-06-19, STEPS: `[50, ]`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
-
-// Executed Command:
-// target/release/substrate
-// benchmark
-// --chain=dev
-// --steps=50
-// --repeat=20
-// --pallet=pallet_vesting
-// --extrinsic=*
-// --execution=wasm
-// --wasm-execution=compiled
-// --heap-pages=4096
-// --output=./frame/vesting/src/weights.rs
-// --template=./.maintain/frame-weight-template.hbs
-
+//! These are not benchmark results. The node runtime can't currently be built with this pallet,
+//! so the benchmark CLI can't be run against it, and every value below is a conservative estimate
+//! set by hand, rounded up well above what the benchmarks in `benchmarking.rs` are expected to
+//! measure. Replace them with the output of the benchmark CLI once it can be run:
+//!
+//! ```sh
+//! target/release/substrate benchmark --chain=dev --steps=50 --repeat=20 \
+//!     --pallet=pallet_vesting --extrinsic=* --execution=wasm --wasm-execution=compiled \
+//!     --heap-pages=4096 --output=./frame/vesting/src/weights.rs \
+//!     --template=./.maintain/frame-weight-template.hbs
+//! ```
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -50,97 +43,615 @@ pub trait WeightInfo {
 	fn vest_other_unlocked(l: u32, ) -> Weight;
 	fn vested_transfer(l: u32, ) -> Weight;
 	fn force_vested_transfer(l: u32, ) -> Weight;
+	fn remove_orphaned_vesting() -> Weight;
+	fn schedule_vested_transfer() -> Weight;
+	fn cancel_scheduled_transfer() -> Weight;
+	fn execute_scheduled_transfer(l: u32, ) -> Weight;
+	fn require_terms() -> Weight;
+	fn accept_terms() -> Weight;
+	fn clear_terms() -> Weight;
+	fn create_standing_order() -> Weight;
+	fn modify_standing_order() -> Weight;
+	fn cancel_standing_order() -> Weight;
+	fn execute_standing_order(l: u32, ) -> Weight;
+	fn create_stream() -> Weight;
+	fn withdraw_from_stream() -> Weight;
+	fn cancel_stream() -> Weight;
+	fn create_position() -> Weight;
+	fn vest_position() -> Weight;
+	fn create_vesting_plan() -> Weight;
+	fn vested_transfer_with_plan(l: u32, ) -> Weight;
+	fn vested_transfer_with_metadata(l: u32, ) -> Weight;
+	fn revoke_vesting_plan(n: u32, ) -> Weight;
+	fn set_contingent_beneficiary() -> Weight;
+	fn set_withholding() -> Weight;
+	fn claim_as_contingent(l: u32, ) -> Weight;
+	fn sweep(l: u32, ) -> Weight;
+	fn set_unlock_multiplier() -> Weight;
+	fn approve_vester() -> Weight;
+	fn revoke_vester() -> Weight;
+	fn pause() -> Weight;
+	fn unpause() -> Weight;
+	fn freeze_progression() -> Weight;
+	fn resume_progression() -> Weight;
+	fn set_min_vested_transfer() -> Weight;
+	fn export_schedule(l: u32, ) -> Weight;
+	fn import_schedule(l: u32, ) -> Weight;
+	fn force_apply_vesting(l: u32, ) -> Weight;
+	fn vest_own_balance(l: u32, ) -> Weight;
+	fn exit_early(l: u32, ) -> Weight;
+	fn set_claims_root() -> Weight;
+	fn claim_vesting(p: u32, ) -> Weight;
+	fn add_vesting_schedule(l: u32, ) -> Weight;
+	fn remove_vesting_schedule(l: u32, ) -> Weight;
+	fn register_auto_vest() -> Weight;
 }
 
 /// Weights for pallet_vesting using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	fn vest_locked(l: u32, ) -> Weight {
-		(42_905_000 as Weight)
-			// Standard Error: 13_000
-			.saturating_add((232_000 as Weight).saturating_mul(l as Weight))
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+		// Hand-set over-estimate, not benchmarked.
+		(62_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
 	}
 	fn vest_unlocked(l: u32, ) -> Weight {
-		(45_650_000 as Weight)
-			// Standard Error: 12_000
-			.saturating_add((215_000 as Weight).saturating_mul(l as Weight))
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+		// Hand-set over-estimate, not benchmarked.
+		(118_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(20 as Weight))
+			.saturating_add(T::DbWeight::get().writes(22 as Weight))
 	}
 	fn vest_other_locked(l: u32, ) -> Weight {
-		(42_273_000 as Weight)
-			// Standard Error: 15_000
-			.saturating_add((246_000 as Weight).saturating_mul(l as Weight))
+		// Hand-set over-estimate, not benchmarked.
+		(64_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(12 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn vest_other_unlocked(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(120_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(21 as Weight))
+			.saturating_add(T::DbWeight::get().writes(22 as Weight))
+	}
+	fn vested_transfer(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(145_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn force_vested_transfer(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(150_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn remove_orphaned_vesting() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(40_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
-	fn vest_other_unlocked(l: u32, ) -> Weight {
-		(45_324_000 as Weight)
-			// Standard Error: 12_000
-			.saturating_add((214_000 as Weight).saturating_mul(l as Weight))
+	fn schedule_vested_transfer() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(75_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn cancel_scheduled_transfer() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(70_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn execute_scheduled_transfer(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(180_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+	}
+	fn require_terms() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(25_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn accept_terms() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(105_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn clear_terms() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn create_standing_order() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(55_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn modify_standing_order() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn cancel_standing_order() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(45_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn execute_standing_order(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(160_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	fn create_stream() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(65_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw_from_stream() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(75_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
-	fn vested_transfer(l: u32, ) -> Weight {
-		(96_661_000 as Weight)
-			// Standard Error: 10_000
-			.saturating_add((211_000 as Weight).saturating_mul(l as Weight))
+	fn cancel_stream() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(85_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
-	fn force_vested_transfer(l: u32, ) -> Weight {
-		(98_812_000 as Weight)
-			// Standard Error: 13_000
-			.saturating_add((139_000 as Weight).saturating_mul(l as Weight))
+	fn create_position() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(90_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn vest_position() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(100_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
 			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
+	fn create_vesting_plan() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn vested_transfer_with_plan(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(135_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	fn vested_transfer_with_metadata(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(135_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	fn revoke_vesting_plan(n: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(20_000_000 as Weight)
+			.saturating_add((107_100_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(n as Weight)))
+	}
+	fn set_contingent_beneficiary() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_withholding() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn claim_as_contingent(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(145_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn sweep(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(135_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn set_unlock_multiplier() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn approve_vester() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn revoke_vester() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(25_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn pause() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn unpause() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn freeze_progression() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn resume_progression() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn set_min_vested_transfer() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn export_schedule(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(75_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	fn import_schedule(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(70_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn force_apply_vesting(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(70_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn vest_own_balance(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(70_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn exit_early(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(110_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn set_claims_root() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn claim_vesting(p: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(135_000_000 as Weight)
+			.saturating_add((2_100_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn add_vesting_schedule(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(60_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn remove_vesting_schedule(l: u32, ) -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(65_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(11 as Weight))
+	}
+	fn register_auto_vest() -> Weight {
+		// Hand-set over-estimate, not benchmarked.
+		(50_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
 }
 
-// For backwards compatibility and tests
+// For backwards compatibility and tests, with the same hand-set over-estimates
 impl WeightInfo for () {
 	fn vest_locked(l: u32, ) -> Weight {
-		(42_905_000 as Weight)
-			// Standard Error: 13_000
-			.saturating_add((232_000 as Weight).saturating_mul(l as Weight))
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+		(62_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(11 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
 	}
 	fn vest_unlocked(l: u32, ) -> Weight {
-		(45_650_000 as Weight)
-			// Standard Error: 12_000
-			.saturating_add((215_000 as Weight).saturating_mul(l as Weight))
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+		(118_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(20 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(22 as Weight))
 	}
 	fn vest_other_locked(l: u32, ) -> Weight {
-		(42_273_000 as Weight)
-			// Standard Error: 15_000
-			.saturating_add((246_000 as Weight).saturating_mul(l as Weight))
+		(64_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(12 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn vest_other_unlocked(l: u32, ) -> Weight {
+		(120_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(21 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(22 as Weight))
+	}
+	fn vested_transfer(l: u32, ) -> Weight {
+		(145_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn force_vested_transfer(l: u32, ) -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn remove_orphaned_vesting() -> Weight {
+		(40_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
-	fn vest_other_unlocked(l: u32, ) -> Weight {
-		(45_324_000 as Weight)
-			// Standard Error: 12_000
-			.saturating_add((214_000 as Weight).saturating_mul(l as Weight))
+	fn schedule_vested_transfer() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn cancel_scheduled_transfer() -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn execute_scheduled_transfer(l: u32, ) -> Weight {
+		(180_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+	}
+	fn require_terms() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn accept_terms() -> Weight {
+		(105_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn clear_terms() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn create_standing_order() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn modify_standing_order() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn cancel_standing_order() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn execute_standing_order(l: u32, ) -> Weight {
+		(160_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn create_stream() -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw_from_stream() -> Weight {
+		(75_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
-	fn vested_transfer(l: u32, ) -> Weight {
-		(96_661_000 as Weight)
-			// Standard Error: 10_000
-			.saturating_add((211_000 as Weight).saturating_mul(l as Weight))
+	fn cancel_stream() -> Weight {
+		(85_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
-	fn force_vested_transfer(l: u32, ) -> Weight {
-		(98_812_000 as Weight)
-			// Standard Error: 13_000
-			.saturating_add((139_000 as Weight).saturating_mul(l as Weight))
+	fn create_position() -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn vest_position() -> Weight {
+		(100_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
+	fn create_vesting_plan() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn vested_transfer_with_plan(l: u32, ) -> Weight {
+		(135_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	fn vested_transfer_with_metadata(l: u32, ) -> Weight {
+		(135_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	fn revoke_vesting_plan(n: u32, ) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((107_100_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(n as Weight)))
+	}
+	fn set_contingent_beneficiary() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_withholding() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn claim_as_contingent(l: u32, ) -> Weight {
+		(145_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn sweep(l: u32, ) -> Weight {
+		(135_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn set_unlock_multiplier() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn approve_vester() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn revoke_vester() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn pause() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn unpause() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn freeze_progression() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn resume_progression() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn set_min_vested_transfer() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn export_schedule(l: u32, ) -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn import_schedule(l: u32, ) -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn force_apply_vesting(l: u32, ) -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn vest_own_balance(l: u32, ) -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn exit_early(l: u32, ) -> Weight {
+		(110_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn set_claims_root() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn claim_vesting(p: u32, ) -> Weight {
+		(135_000_000 as Weight)
+			.saturating_add((2_100_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn add_vesting_schedule(l: u32, ) -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn remove_vesting_schedule(l: u32, ) -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(11 as Weight))
+	}
+	fn register_auto_vest() -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
 }